@@ -0,0 +1,123 @@
+//! SipHash (the std default) against `FastMap`, on the key shapes hot puzzle loops use.
+//!
+//! Run with `cargo bench -p advent-common --bench hashing`. Each case reports the best of a few
+//! rounds, in nanoseconds per map operation.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::hint::black_box;
+use std::time::Instant;
+
+use advent_common::fasthash::{FastBuildHasher, FastMap};
+use advent_common::intern::StringInterner;
+
+const ROUNDS: usize = 5;
+
+fn best_ns_per_op(ops: usize, mut run: impl FnMut()) -> f64 {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed().as_nanos() as f64 / ops as f64
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn report(name: &str, sip: f64, fast: f64) {
+    println!(
+        "{name:<28} siphash {sip:>7.2} ns/op   fast {fast:>7.2} ns/op   {:.1}x",
+        sip / fast
+    );
+}
+
+/// Accumulates into a map the way 2024 day22 totals prices per sequence of price changes.
+fn count_keys<K: Hash + Eq + Copy, S: BuildHasher + Default>(keys: &[K]) -> usize {
+    let mut map: HashMap<K, u64, S> = HashMap::default();
+    for &k in keys {
+        *map.entry(k).or_insert(0) += 1;
+    }
+    black_box(map.len())
+}
+
+/// Price-change windows, i.e. four digits in `-9..=9` each.
+fn change_windows(n: usize) -> Vec<(i64, i64, i64, i64)> {
+    let mut x = 123u64;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let d = |shift: u32| ((x >> shift) % 19) as i64 - 9;
+            (d(0), d(8), d(16), d(24))
+        })
+        .collect()
+}
+
+/// Three-letter device names, as in 2024 day23 and 2025 day11.
+fn device_names(n: usize) -> Vec<String> {
+    (0..n)
+        .map(|i| {
+            let i = (i * 7919) % (26 * 26 * 26);
+            [i / 676, i / 26 % 26, i % 26]
+                .iter()
+                .map(|&c| (b'a' + c as u8) as char)
+                .collect()
+        })
+        .collect()
+}
+
+fn main() {
+    let windows = change_windows(2_000_000);
+    report(
+        "(i64, i64, i64, i64) counts",
+        best_ns_per_op(windows.len(), || {
+            count_keys::<_, std::hash::RandomState>(&windows);
+        }),
+        best_ns_per_op(windows.len(), || {
+            count_keys::<_, FastBuildHasher>(&windows);
+        }),
+    );
+
+    let names = device_names(1_000_000);
+    // The same steps as `intern_ref`: a borrowed lookup, and only on a miss an owned copy for
+    // the map and one for the name list, so both sides allocate equally.
+    let sip = best_ns_per_op(names.len(), || {
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut owned: Vec<String> = Vec::new();
+        for name in &names {
+            let id = match ids.get(name.as_str()) {
+                Some(&id) => id,
+                None => {
+                    let id = owned.len();
+                    ids.insert(name.clone(), id);
+                    owned.push(name.clone());
+                    id
+                }
+            };
+            black_box(id);
+        }
+    });
+    let fast = best_ns_per_op(names.len(), || {
+        let mut ids = StringInterner::new();
+        for name in &names {
+            black_box(ids.intern_ref(name.as_str()));
+        }
+    });
+    report("name -> id interning", sip, fast);
+
+    let pairs = names
+        .windows(2)
+        .map(|w| (w[0].as_str(), w[1].as_str()))
+        .collect::<Vec<_>>();
+    let sip_map = pairs.iter().map(|&p| (p, 1u64)).collect::<HashMap<_, _>>();
+    let fast_map = pairs.iter().map(|&p| (p, 1u64)).collect::<FastMap<_, _>>();
+    report(
+        "(&str, &str) lookups",
+        best_ns_per_op(pairs.len(), || {
+            black_box(pairs.iter().map(|p| sip_map[p]).sum::<u64>());
+        }),
+        best_ns_per_op(pairs.len(), || {
+            black_box(pairs.iter().map(|p| fast_map[p]).sum::<u64>());
+        }),
+    );
+}
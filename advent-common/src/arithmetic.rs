@@ -1,4 +1,12 @@
+//! Binary operators and the expressions built from them.
+//!
+//! Puzzles disagree on how `1 + 2 * 3` reads, so [`evaluate`] takes a [`Precedence`] table:
+//! everything equal and left to right, the usual school rules, or any custom order.
+
+use std::ops::RangeInclusive;
+
 use crate::AdventError;
+use crate::digits::Digits;
 
 #[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub enum Operator {
@@ -6,8 +14,84 @@ pub enum Operator {
     Subtract,
     Multiply,
     Divide,
+    /// Writes the right operand's digits after the left's, so `12 || 345` is `12345`.
+    Concat,
+}
+
+impl Operator {
+    pub const ALL: [Operator; 5] = [
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Concat,
+    ];
+
+    /// `a op b`, or `None` if the result would overflow, go negative or divide by zero.
+    pub fn apply(self, a: u64, b: u64) -> Option<u64> {
+        match self {
+            Self::Add => a.checked_add(b),
+            Self::Subtract => a.checked_sub(b),
+            Self::Multiply => a.checked_mul(b),
+            Self::Divide => a.checked_div(b),
+            Self::Concat => a.concat(b),
+        }
+    }
+
+    /// Every left operand `a` with `a op b == result`, as a range that is empty when there is
+    /// none: subtraction undoes addition, an exact division undoes multiplication and
+    /// stripping `b`'s digits off the end undoes concatenation. Integer division and
+    /// multiplying by zero have many possible left operands.
+    pub fn unapply(self, result: u64, b: u64) -> RangeInclusive<u64> {
+        let one = |a: Option<u64>| a.map_or(NONE, |a| a..=a);
+        match self {
+            Self::Add => one(result.checked_sub(b)),
+            Self::Subtract => one(result.checked_add(b)),
+            Self::Multiply if b == 0 => {
+                if result == 0 {
+                    0..=u64::MAX
+                } else {
+                    NONE
+                }
+            }
+            Self::Multiply => one(result.is_multiple_of(b).then(|| result / b)),
+            Self::Divide if b == 0 => NONE,
+            Self::Divide => match result.checked_mul(b) {
+                Some(lo) => lo..=lo.saturating_add(b - 1),
+                None => NONE,
+            },
+            Self::Concat => one(u64::pow10(b.num_digits()).and_then(|shift| {
+                let prefix = result.checked_sub(b)?;
+                (prefix % shift == 0).then(|| prefix / shift)
+            })),
+        }
+    }
+
+    /// The value `e` with `e op x == x` for every `x`, if there is one.
+    pub fn identity(self) -> Option<u64> {
+        match self {
+            Self::Add => Some(0),
+            Self::Multiply => Some(1),
+            Self::Subtract | Self::Divide | Self::Concat => None,
+        }
+    }
+
+    /// Applies the operator across `values` from left to right. An empty list gives the
+    /// identity, and `None` also covers overflow as in [`Operator::apply`].
+    pub fn fold(self, values: impl IntoIterator<Item = u64>) -> Option<u64> {
+        let mut values = values.into_iter();
+        let first = values.next().or_else(|| self.identity())?;
+        values.try_fold(first, |acc, v| self.apply(acc, v))
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
 }
 
+/// An empty [`Operator::unapply`] result.
+const NONE: RangeInclusive<u64> = RangeInclusive::new(1, 0);
+
 impl TryFrom<char> for Operator {
     type Error = AdventError;
 
@@ -27,7 +111,12 @@ impl TryFrom<char> for Operator {
 impl<'a> TryFrom<&'a str> for Operator {
     type Error = AdventError;
 
+    /// Like the `char` conversion on the first character, except that `"||"` is
+    /// [`Operator::Concat`] as in [`tokenize`].
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        if value.starts_with("||") {
+            return Ok(Self::Concat);
+        }
         let c = value
             .chars()
             .next()
@@ -35,3 +124,240 @@ impl<'a> TryFrom<&'a str> for Operator {
         Operator::try_from(c)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a - b - c` is `a - (b - c)`.
+    Right,
+}
+
+/// How tightly each operator binds (higher first) and which way equal levels group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precedence {
+    levels: [u8; Operator::ALL.len()],
+    associativity: [Associativity; Operator::ALL.len()],
+}
+
+impl Precedence {
+    /// Every operator on one level, evaluated strictly left to right.
+    pub const fn left_to_right() -> Self {
+        Self {
+            levels: [0; Operator::ALL.len()],
+            associativity: [Associativity::Left; Operator::ALL.len()],
+        }
+    }
+
+    /// `*` and `/` before `+` and `-`, which come before `||`, all grouping to the left.
+    pub fn standard() -> Self {
+        Self::left_to_right()
+            .with_level(Operator::Multiply, 2)
+            .with_level(Operator::Divide, 2)
+            .with_level(Operator::Add, 1)
+            .with_level(Operator::Subtract, 1)
+    }
+
+    pub fn with_level(mut self, operator: Operator, level: u8) -> Self {
+        self.levels[operator.index()] = level;
+        self
+    }
+
+    pub fn with_associativity(mut self, operator: Operator, associativity: Associativity) -> Self {
+        self.associativity[operator.index()] = associativity;
+        self
+    }
+
+    pub fn level(&self, operator: Operator) -> u8 {
+        self.levels[operator.index()]
+    }
+
+    pub fn associativity(&self, operator: Operator) -> Associativity {
+        self.associativity[operator.index()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Number(u64),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+/// Splits an expression into numbers, operators and parentheses, ignoring whitespace. `||`
+/// is read as [`Operator::Concat`].
+pub fn tokenize(s: &str) -> Result<Vec<Token>, AdventError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '0'..='9' => {
+                let mut end = i + 1;
+                while let Some(&(j, d)) = chars.peek().filter(|(_, d)| d.is_ascii_digit()) {
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                Token::Number(s[i..end].parse().map_err(|_| {
+                    AdventError::InputParseError(
+                        format!("column {}: {:?} does not fit in u64", i + 1, &s[i..end]).into(),
+                    )
+                })?)
+            }
+            '|' => {
+                if chars.next_if(|&(_, d)| d == '|').is_none() {
+                    return Err(AdventError::InputParseError(
+                        format!("column {}: expected \"||\"", i + 1).into(),
+                    ));
+                }
+                Token::Operator(Operator::Concat)
+            }
+            c => Token::Operator(Operator::try_from(c).map_err(|_| {
+                AdventError::InputParseError(
+                    format!("column {}: unexpected {c:?} in expression", i + 1).into(),
+                )
+            })?),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Evaluates an infix expression such as `2 * (3 + 4) || 1` under `precedence`.
+pub fn evaluate(s: &str, precedence: &Precedence) -> Result<u64, AdventError> {
+    let tokens = tokenize(s)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        at: 0,
+        precedence,
+    };
+    let value = parser.expression(0)?;
+    match parser.tokens.get(parser.at) {
+        None => Ok(value),
+        Some(token) => Err(parser.error(format!("unexpected {token:?}"))),
+    }
+}
+
+/// Precedence climbing over a token slice.
+struct Parser<'t, 'p> {
+    tokens: &'t [Token],
+    at: usize,
+    precedence: &'p Precedence,
+}
+
+impl Parser<'_, '_> {
+    fn error(&self, message: String) -> AdventError {
+        AdventError::InputParseError(format!("token {}: {message}", self.at + 1).into())
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.at).copied();
+        self.at += 1;
+        token
+    }
+
+    /// Parses operands joined by operators binding at least as tightly as `min_level`. It is
+    /// wider than a level so that one past the top level still fits.
+    fn expression(&mut self, min_level: u16) -> Result<u64, AdventError> {
+        let mut lhs = self.operand()?;
+        while let Some(&Token::Operator(op)) = self.tokens.get(self.at) {
+            let level = u16::from(self.precedence.level(op));
+            if level < min_level {
+                break;
+            }
+            self.at += 1;
+            let next_min = match self.precedence.associativity(op) {
+                Associativity::Left => level + 1,
+                Associativity::Right => level,
+            };
+            let rhs = self.expression(next_min)?;
+            lhs = op
+                .apply(lhs, rhs)
+                .ok_or_else(|| self.error(format!("{lhs} {op:?} {rhs} is out of range")))?;
+        }
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<u64, AdventError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Open) => {
+                let value = self.expression(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(self.error("expected \")\"".into())),
+                }
+            }
+            Some(token) => Err(self.error(format!("expected a number, found {token:?}"))),
+            None => Err(self.error("expected a number, found the end".into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        assert_eq!(Some(12345), Operator::Concat.apply(12, 345));
+        assert_eq!(Some(10), Operator::Concat.apply(1, 0));
+        assert_eq!(Some(110), Operator::Concat.apply(1, 10));
+        assert_eq!(None, Operator::Concat.apply(u64::MAX / 10, 12));
+        assert_eq!(None, Operator::Subtract.apply(1, 2));
+        assert_eq!(None, Operator::Divide.apply(1, 0));
+        assert_eq!(Some(24), Operator::Multiply.fold([2, 3, 4]));
+        assert_eq!(Some(0), Operator::Add.fold([]));
+        assert_eq!(None, Operator::Concat.fold([]));
+
+        for op in Operator::ALL {
+            for (a, b) in [(12, 345), (7, 0), (0, 7), (100, 10), (6, 3)] {
+                if let Some(result) = op.apply(a, b) {
+                    assert!(op.unapply(result, b).contains(&a), "{op:?} {a} {b}");
+                }
+            }
+        }
+        assert_eq!(12..=12, Operator::Concat.unapply(12345, 345));
+        assert!(Operator::Concat.unapply(12346, 345).is_empty());
+        assert!(Operator::Multiply.unapply(7, 2).is_empty());
+        assert_eq!(20..=24, Operator::Divide.unapply(4, 5));
+    }
+
+    #[test]
+    fn precedence() {
+        let flat = Precedence::left_to_right();
+        assert_eq!(71, evaluate("1 + 2 * 3 + 4 * 5 + 6", &flat).unwrap());
+        assert_eq!(51, evaluate("1 + (2 * 3) + (4 * (5 + 6))", &flat).unwrap());
+        assert_eq!(
+            33,
+            evaluate("1 + 2 * 3 + 4 * 5 + 6", &Precedence::standard()).unwrap()
+        );
+
+        let add_first = flat.with_level(Operator::Add, 1);
+        assert_eq!(231, evaluate("1 + 2 * 3 + 4 * 5 + 6", &add_first).unwrap());
+        assert_eq!(126, evaluate("12 || 3 + 3", &add_first).unwrap());
+
+        let right = flat.with_associativity(Operator::Subtract, Associativity::Right);
+        assert_eq!(5, evaluate("10 - 3 - 2", &flat).unwrap());
+        assert_eq!(9, evaluate("10 - 3 - 2", &right).unwrap());
+
+        let top = flat.with_level(Operator::Subtract, u8::MAX);
+        assert_eq!(10, evaluate("2 * 10 - 3 - 2", &top).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let flat = Precedence::left_to_right();
+        assert!(evaluate("1 +", &flat).is_err());
+        assert!(evaluate("(1 + 2", &flat).is_err());
+        assert!(evaluate("1 2", &flat).is_err());
+        assert!(evaluate("1 | 2", &flat).is_err());
+        assert!(Operator::try_from('|').is_err());
+        assert!(Operator::try_from("|").is_err());
+        assert_eq!(Operator::Concat, Operator::try_from("||").unwrap());
+        assert!(evaluate("1 - 2", &flat).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! Cellular automata on rectangular grids.
+//!
+//! An [`Automaton`] holds one state per cell and advances them all by a rule that sees a cell
+//! and its neighbours. Only cells on the active frontier, those that changed in the last step
+//! and their neighbours, are re-evaluated, so a simulation that is settling down gets cheaper
+//! every step. This relies on the rule depending on nothing but the cell's own neighbourhood.
+//!
+//! [`Automaton::run`] steps until the grid stops changing or returns to an earlier state. Repeats
+//! are looked up by a 64-bit hash of the cells, updated incrementally as cells change, and then
+//! confirmed by comparing the cells themselves.
+
+use std::collections::BTreeSet;
+use std::hash::{BuildHasher, Hash};
+
+use crate::AdventError;
+use crate::cycle::CycleInfo;
+use crate::fasthash::{FastBuildHasher, FastMap};
+
+/// Which cells around a cell count as its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// The four orthogonally adjacent cells.
+    VonNeumann,
+    /// The eight surrounding cells, diagonals included.
+    Moore,
+}
+
+impl Neighbourhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Self::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Self::Moore => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// What lies past the edge of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Boundary {
+    /// Nothing: edge cells simply have fewer neighbours.
+    Closed,
+    /// The opposite edge, making the grid a torus.
+    Wrap,
+}
+
+/// How a step writes new states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpdateMode {
+    /// Every cell sees its neighbours as they were before the step.
+    DoubleBuffered,
+    /// Cells are updated in row-major order and later cells already see earlier updates. A
+    /// change also brings its later neighbours into the same sweep, even if they were not on
+    /// the frontier.
+    InPlace,
+}
+
+/// Why [`Automaton::run`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A step changed nothing. `generation` steps had changed something before it.
+    FixedPoint { generation: usize },
+    /// The grid is back in a state it had before, counting generations from the start.
+    Cycle(CycleInfo),
+    /// The generation limit was reached first.
+    Limit,
+}
+
+/// A cell as seen by a rule: its position, its state and its neighbours' states.
+pub struct Cell<'a, T> {
+    automaton: &'a Automaton<T>,
+    index: usize,
+}
+
+impl<T> Cell<'_, T> {
+    pub fn pos(&self) -> (usize, usize) {
+        (
+            self.index / self.automaton.cols,
+            self.index % self.automaton.cols,
+        )
+    }
+
+    pub fn state(&self) -> &T {
+        &self.automaton.cells[self.index]
+    }
+
+    /// The cell `dr` rows and `dc` columns away, if the boundary allows it. It need not be a
+    /// neighbour, but the frontier only tracks the cells in the neighbourhood.
+    pub fn offset(&self, dr: isize, dc: isize) -> Option<&T> {
+        let a = self.automaton;
+        let (r, c) = self.pos();
+        let r = wrap_or_clip(r, dr, a.rows, a.boundary)?;
+        let c = wrap_or_clip(c, dc, a.cols, a.boundary)?;
+        Some(&a.cells[r * a.cols + c])
+    }
+
+    pub fn neighbours(&self) -> impl Iterator<Item = &T> + '_ {
+        self.automaton
+            .neighbour_indices(self.index)
+            .map(|i| &self.automaton.cells[i])
+    }
+
+    /// Number of neighbours whose state satisfies `pred`.
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.neighbours().filter(|s| pred(s)).count()
+    }
+}
+
+/// Grid of cell states with a frontier of cells that may change on the next step.
+#[derive(Debug, Clone)]
+pub struct Automaton<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+    neighbourhood: Neighbourhood,
+    boundary: Boundary,
+    mode: UpdateMode,
+    /// Cells to evaluate on the next step, in ascending index order.
+    frontier: Vec<usize>,
+    /// `queued[i]` is the generation whose frontier last took in cell `i`.
+    queued: Vec<usize>,
+    generation: usize,
+    hash: u64,
+}
+
+impl<T> Automaton<T> {
+    fn neighbour_indices(&self, index: usize) -> impl Iterator<Item = usize> + use<T> {
+        let (rows, cols, boundary) = (self.rows, self.cols, self.boundary);
+        let (r, c) = (index / cols, index % cols);
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |&(dr, dc)| {
+                let r = wrap_or_clip(r, dr, rows, boundary)?;
+                let c = wrap_or_clip(c, dc, cols, boundary)?;
+                Some(r * cols + c)
+            })
+    }
+}
+
+impl<T: Clone + PartialEq + Hash> Automaton<T> {
+    /// A `rows` x `cols` automaton over `cells` in row-major order, with Moore neighbourhoods,
+    /// closed edges and double-buffered updates.
+    pub fn new(rows: usize, cols: usize, cells: Vec<T>) -> Self {
+        assert_eq!(rows * cols, cells.len(), "expected {rows} x {cols} cells");
+        let hash = cells
+            .iter()
+            .enumerate()
+            .fold(0u64, |h, (i, s)| h.wrapping_add(cell_hash(i, s)));
+        Self {
+            rows,
+            cols,
+            neighbourhood: Neighbourhood::Moore,
+            boundary: Boundary::Closed,
+            mode: UpdateMode::DoubleBuffered,
+            frontier: (0..cells.len()).collect(),
+            queued: vec![0; cells.len()],
+            generation: 0,
+            hash,
+            cells,
+        }
+    }
+
+    /// Automaton with the state `parse` gives each character. All lines must have the same
+    /// number of characters.
+    pub fn from_lines<S: AsRef<str>>(
+        lines: &[S],
+        parse: impl Fn(char) -> T,
+    ) -> Result<Self, AdventError> {
+        let cols = lines.first().map_or(0, |l| l.as_ref().chars().count());
+        let mut cells = Vec::with_capacity(lines.len() * cols);
+        for (r, line) in lines.iter().enumerate() {
+            let before = cells.len();
+            cells.extend(line.as_ref().chars().map(&parse));
+            let width = cells.len() - before;
+            if width != cols {
+                return Err(AdventError::InputParseError(
+                    format!("grid row has {width} cells, expected {cols}").into(),
+                )
+                .at_line(r));
+            }
+        }
+        Ok(Self::new(lines.len(), cols, cells))
+    }
+
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Self {
+        Self {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    pub fn with_boundary(self, boundary: Boundary) -> Self {
+        Self { boundary, ..self }
+    }
+
+    pub fn with_mode(self, mode: UpdateMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> &T {
+        assert!(r < self.rows && c < self.cols, "({r}, {c}) out of range");
+        &self.cells[r * self.cols + c]
+    }
+
+    /// Every state in row-major order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Number of cells whose state satisfies `pred`.
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.cells.iter().filter(|s| pred(s)).count()
+    }
+
+    /// Number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Number of cells the next step will evaluate.
+    pub fn frontier_len(&self) -> usize {
+        self.frontier.len()
+    }
+
+    /// Sets cell `index`, queueing it and its neighbours for the next step.
+    fn update(&mut self, index: usize, state: T, next: &mut Vec<usize>) {
+        self.hash = self
+            .hash
+            .wrapping_sub(cell_hash(index, &self.cells[index]))
+            .wrapping_add(cell_hash(index, &state));
+        self.cells[index] = state;
+
+        let stamp = self.generation + 1;
+        for i in std::iter::once(index).chain(self.neighbour_indices(index)) {
+            if self.queued[i] != stamp {
+                self.queued[i] = stamp;
+                next.push(i);
+            }
+        }
+    }
+
+    /// Applies `rule` to every cell on the frontier and returns how many cells changed.
+    pub fn step(&mut self, mut rule: impl FnMut(&Cell<'_, T>) -> T) -> usize {
+        let frontier = std::mem::take(&mut self.frontier);
+        let mut next = Vec::new();
+        let mut changed = 0;
+        match self.mode {
+            UpdateMode::DoubleBuffered => {
+                let updates = frontier
+                    .iter()
+                    .filter_map(|&index| {
+                        let state = rule(&Cell {
+                            automaton: self,
+                            index,
+                        });
+                        (state != self.cells[index]).then_some((index, state))
+                    })
+                    .collect::<Vec<_>>();
+                changed = updates.len();
+                for (index, state) in updates {
+                    self.update(index, state, &mut next);
+                }
+            }
+            UpdateMode::InPlace => {
+                // A change can affect cells later in this sweep that were not on the frontier,
+                // so the sweep takes them in as it goes, always moving to the smallest index.
+                let mut sweep = frontier.into_iter().collect::<BTreeSet<_>>();
+                while let Some(index) = sweep.pop_first() {
+                    let state = rule(&Cell {
+                        automaton: self,
+                        index,
+                    });
+                    if state != self.cells[index] {
+                        changed += 1;
+                        self.update(index, state, &mut next);
+                        sweep.extend(self.neighbour_indices(index).filter(|&i| i > index));
+                    }
+                }
+            }
+        }
+        next.sort_unstable();
+        self.frontier = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until a step changes nothing, the grid repeats an earlier state, or
+    /// `max_generations` steps have been taken.
+    ///
+    /// Candidate repeats are found by hash and confirmed by comparing the cells, so this keeps a
+    /// copy of every state it passes through.
+    pub fn run(
+        &mut self,
+        mut rule: impl FnMut(&Cell<'_, T>) -> T,
+        max_generations: usize,
+    ) -> Outcome {
+        let mut seen: FastMap<u64, Vec<(usize, Vec<T>)>> = FastMap::default();
+        seen.entry(self.hash)
+            .or_default()
+            .push((self.generation, self.cells.clone()));
+        let start = self.generation;
+        while self.generation - start < max_generations {
+            if self.step(&mut rule) == 0 {
+                return Outcome::FixedPoint {
+                    generation: self.generation - 1,
+                };
+            }
+            let states = seen.entry(self.hash).or_default();
+            if let Some(&(first, _)) = states.iter().find(|(_, cells)| *cells == self.cells) {
+                return Outcome::Cycle(CycleInfo {
+                    prefix: first,
+                    period: self.generation - first,
+                });
+            }
+            states.push((self.generation, self.cells.clone()));
+        }
+        Outcome::Limit
+    }
+}
+
+/// Hash of one cell, combined over the grid by wrapping addition so updates are O(1).
+///
+/// [`FastBuildHasher`] alone is too close to linear for that: flipping a cell would shift the
+/// sum by nearly the same amount wherever the cell is. The splitmix64 finalizer breaks that up.
+fn cell_hash<T: Hash>(index: usize, state: &T) -> u64 {
+    let mut z = FastBuildHasher::default().hash_one((index, state));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn wrap_or_clip(x: usize, dx: isize, len: usize, boundary: Boundary) -> Option<usize> {
+    match boundary {
+        Boundary::Closed => x.checked_add_signed(dx).filter(|&x| x < len),
+        Boundary::Wrap => Some((x as isize + dx).rem_euclid(len as isize) as usize),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::Hasher;
+
+    use super::*;
+
+    fn life(cell: &Cell<'_, bool>) -> bool {
+        let alive = cell.count(|&s| s);
+        alive == 3 || (*cell.state() && alive == 2)
+    }
+
+    fn grid(rows: &[&str]) -> Automaton<bool> {
+        Automaton::from_lines(rows, |c| c == '#').unwrap()
+    }
+
+    #[test]
+    fn game_of_life() {
+        let mut block = grid(&["....", ".##.", ".##.", "...."]);
+        assert_eq!(Outcome::FixedPoint { generation: 0 }, block.run(life, 10));
+        assert_eq!(0, block.frontier_len());
+
+        let mut blinker = grid(&[".....", "..#..", "..#..", "..#..", "....."]);
+        assert_eq!(
+            Outcome::Cycle(CycleInfo {
+                prefix: 0,
+                period: 2
+            }),
+            blinker.run(life, 10)
+        );
+        assert!(*blinker.get(2, 1) || *blinker.get(1, 2));
+
+        // A glider crosses a 6x6 torus diagonally, one cell every 4 generations.
+        let mut glider = grid(&[".#....", "..#...", "###...", "......", "......", "......"])
+            .with_boundary(Boundary::Wrap);
+        let outcome = glider.run(life, 100);
+        assert_eq!(
+            Outcome::Cycle(CycleInfo {
+                prefix: 0,
+                period: 24
+            }),
+            outcome
+        );
+        assert_eq!(5, glider.count(|&s| s));
+    }
+
+    #[test]
+    fn update_modes() {
+        // Each cell becomes the largest of itself and its neighbours.
+        let spread = |cell: &Cell<'_, u8>| {
+            cell.neighbours()
+                .copied()
+                .max()
+                .unwrap_or(0)
+                .max(*cell.state())
+        };
+        let row = |mode| {
+            Automaton::new(1, 5, vec![9, 0, 0, 0, 0])
+                .with_neighbourhood(Neighbourhood::VonNeumann)
+                .with_mode(mode)
+        };
+
+        let mut buffered = row(UpdateMode::DoubleBuffered);
+        assert_eq!(1, buffered.step(spread));
+        assert_eq!([9, 9, 0, 0, 0], buffered.cells());
+        assert_eq!(3, buffered.frontier_len());
+        assert_eq!(
+            Outcome::FixedPoint { generation: 4 },
+            buffered.run(spread, 10)
+        );
+
+        // Cells off the frontier still see an in-place change made earlier in the same sweep.
+        let count_then_copy = |cell: &Cell<'_, u8>| match cell.pos() {
+            (0, 0) => (*cell.state() + 1).min(2),
+            _ if cell.offset(0, -1) == Some(&2) => 2,
+            _ => *cell.state(),
+        };
+        let mut sweep = Automaton::new(1, 4, vec![0; 4])
+            .with_neighbourhood(Neighbourhood::VonNeumann)
+            .with_mode(UpdateMode::InPlace);
+        assert_eq!(1, sweep.step(count_then_copy));
+        assert_eq!([1, 0, 0, 0], sweep.cells());
+        assert_eq!(2, sweep.frontier_len());
+        assert_eq!(4, sweep.step(count_then_copy));
+        assert_eq!([2; 4], sweep.cells());
+
+        let mut in_place = row(UpdateMode::InPlace);
+        assert_eq!(4, in_place.step(spread));
+        assert_eq!([9; 5], in_place.cells());
+        assert_eq!(
+            Outcome::FixedPoint { generation: 1 },
+            in_place.run(spread, 10)
+        );
+    }
+
+    #[test]
+    fn roll_removal() {
+        // Rolls with fewer than four roll neighbours are taken away until none are left to take.
+        let mut floor = grid(&[
+            "..##.####.",
+            "###.#.#.##",
+            "#####.#.##",
+            "#.####..#.",
+            "##.####.##",
+            ".#######.#",
+            ".#.#.#.###",
+            "#.###.####",
+            ".########.",
+            "#.#.###.#.",
+        ])
+        .with_mode(UpdateMode::InPlace);
+        let before = floor.count(|&s| s);
+        while floor.step(|cell| *cell.state() && cell.count(|&s| s) >= 4) > 0 {}
+        assert_eq!(43, before - floor.count(|&s| s));
+    }
+
+    /// A state whose hash ignores its value, so every grid of them hashes the same.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Opaque(u8);
+
+    impl Hash for Opaque {
+        fn hash<H: Hasher>(&self, _: &mut H) {}
+    }
+
+    #[test]
+    fn hash_collisions_are_not_cycles() {
+        let count_to_five = |cell: &Cell<'_, Opaque>| Opaque((cell.state().0 + 1).min(5));
+        let mut counter = Automaton::new(1, 1, vec![Opaque(0)]);
+        assert_eq!(
+            Outcome::FixedPoint { generation: 5 },
+            counter.run(count_to_five, 10)
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! Bit-packed boolean grids.
+//!
+//! Each row of a [`BitGrid`] is stored as `u64` words, so whole-grid operations such as AND,
+//! shifts and neighbour counts process 64 cells per instruction. Bits past the last column are
+//! always kept clear, which lets [`BitGrid::count_ones`] and comparisons work on raw words.
+
+use std::fmt;
+
+use crate::AdventError;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Fixed-size grid of booleans addressed by `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    data: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let stride = cols.div_ceil(WORD_BITS);
+        Self {
+            rows,
+            cols,
+            stride,
+            data: vec![0; rows * stride],
+        }
+    }
+
+    /// Grid with a cell set wherever `is_set` holds for the character at that position. All
+    /// lines must have the same number of characters.
+    pub fn from_lines<S: AsRef<str>>(
+        lines: &[S],
+        is_set: impl Fn(char) -> bool,
+    ) -> Result<Self, AdventError> {
+        let cols = lines.first().map_or(0, |l| l.as_ref().chars().count());
+        let mut grid = Self::new(lines.len(), cols);
+        for (r, line) in lines.iter().enumerate() {
+            let mut width = 0;
+            for (c, ch) in line.as_ref().chars().enumerate() {
+                if c < cols && is_set(ch) {
+                    grid.set(r, c);
+                }
+                width += 1;
+            }
+            if width != cols {
+                return Err(AdventError::InputParseError(
+                    format!("grid row has {width} cells, expected {cols}").into(),
+                )
+                .at_line(r));
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn index(&self, r: usize, c: usize) -> (usize, u64) {
+        assert!(
+            r < self.rows && c < self.cols,
+            "cell ({r}, {c}) out of range for {}x{} grid",
+            self.rows,
+            self.cols
+        );
+        (r * self.stride + c / WORD_BITS, 1 << (c % WORD_BITS))
+    }
+
+    pub fn test(&self, r: usize, c: usize) -> bool {
+        let (i, mask) = self.index(r, c);
+        self.data[i] & mask != 0
+    }
+
+    pub fn set(&mut self, r: usize, c: usize) {
+        let (i, mask) = self.index(r, c);
+        self.data[i] |= mask;
+    }
+
+    pub fn clear(&mut self, r: usize, c: usize) {
+        let (i, mask) = self.index(r, c);
+        self.data[i] &= !mask;
+    }
+
+    pub fn assign(&mut self, r: usize, c: usize, value: bool) {
+        if value {
+            self.set(r, c);
+        } else {
+            self.clear(r, c);
+        }
+    }
+
+    pub fn toggle(&mut self, r: usize, c: usize) {
+        let (i, mask) = self.index(r, c);
+        self.data[i] ^= mask;
+    }
+
+    /// Number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|&w| w == 0)
+    }
+
+    /// The words backing row `r`, lowest column in the lowest bit.
+    pub fn row_words(&self, r: usize) -> &[u64] {
+        &self.data[r * self.stride..(r + 1) * self.stride]
+    }
+
+    /// Combines row `r` word by word with `words`, laid out as [`BitGrid::row_words`] returns
+    /// them. Bits past the last column are ignored.
+    fn row_zip_assign(&mut self, r: usize, words: &[u64], op: impl Fn(u64, u64) -> u64) {
+        assert!(r < self.rows, "row {r} out of range for {} rows", self.rows);
+        assert_eq!(
+            self.stride,
+            words.len(),
+            "row has {} words, got {}",
+            self.stride,
+            words.len()
+        );
+        let row = &mut self.data[r * self.stride..(r + 1) * self.stride];
+        for (a, &b) in row.iter_mut().zip(words) {
+            *a = op(*a, b);
+        }
+        let used = self.cols % WORD_BITS;
+        if used != 0 {
+            row[self.stride - 1] &= (1u64 << used) - 1;
+        }
+    }
+
+    /// ANDs row `r` with `words`, e.g. a row of another grid of the same width.
+    pub fn and_row_assign(&mut self, r: usize, words: &[u64]) {
+        self.row_zip_assign(r, words, |a, b| a & b);
+    }
+
+    pub fn or_row_assign(&mut self, r: usize, words: &[u64]) {
+        self.row_zip_assign(r, words, |a, b| a | b);
+    }
+
+    pub fn xor_row_assign(&mut self, r: usize, words: &[u64]) {
+        self.row_zip_assign(r, words, |a, b| a ^ b);
+    }
+
+    /// Set cells in row-major order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let stride = self.stride;
+        self.data.iter().enumerate().flat_map(move |(i, &w)| {
+            let (r, base) = (i / stride, (i % stride) * WORD_BITS);
+            let mut w = w;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit = w.trailing_zeros() as usize;
+                w &= w - 1;
+                Some((r, base + bit))
+            })
+        })
+    }
+
+    fn zip_assign(&mut self, other: &BitGrid, op: impl Fn(u64, u64) -> u64) {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "grid sizes differ: {}x{} vs {}x{}",
+            self.rows,
+            self.cols,
+            other.rows,
+            other.cols
+        );
+        for (a, &b) in self.data.iter_mut().zip(&other.data) {
+            *a = op(*a, b);
+        }
+    }
+
+    pub fn and_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a & b);
+    }
+
+    pub fn or_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a | b);
+    }
+
+    pub fn xor_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a ^ b);
+    }
+
+    /// Clears every cell that is set in `other`.
+    pub fn and_not_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a & !b);
+    }
+
+    /// Inverts every cell.
+    pub fn not_assign(&mut self) {
+        for w in &mut self.data {
+            *w = !*w;
+        }
+        self.clear_tail();
+    }
+
+    /// Clears the unused bits past the last column of every row.
+    fn clear_tail(&mut self) {
+        let used = self.cols % WORD_BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1u64 << used) - 1;
+        for r in 0..self.rows {
+            self.data[(r + 1) * self.stride - 1] &= mask;
+        }
+    }
+
+    /// The grid moved by `dr` rows and `dc` columns. Cells moved off the edge are dropped and
+    /// vacated cells are clear, so `shifted(0, 1).test(r, c) == test(r, c - 1)`.
+    pub fn shifted(&self, dr: isize, dc: isize) -> BitGrid {
+        let mut out = BitGrid::new(self.rows, self.cols);
+        let (ws, bits) = (dc.unsigned_abs() / WORD_BITS, dc.unsigned_abs() % WORD_BITS);
+        for r in 0..self.rows {
+            let Some(src_r) = r.checked_add_signed(-dr).filter(|&s| s < self.rows) else {
+                continue;
+            };
+            let src = self.row_words(src_r);
+            let dst = &mut out.data[r * self.stride..(r + 1) * self.stride];
+            for (w, word) in dst.iter_mut().enumerate() {
+                let at = |i: Option<usize>| i.and_then(|i| src.get(i)).copied().unwrap_or(0);
+                *word = if dc >= 0 {
+                    let lo = w.checked_sub(ws);
+                    let carry = if bits == 0 {
+                        0
+                    } else {
+                        at(lo.and_then(|i| i.checked_sub(1))) >> (WORD_BITS - bits)
+                    };
+                    at(lo) << bits | carry
+                } else {
+                    let hi = w + ws;
+                    let carry = if bits == 0 {
+                        0
+                    } else {
+                        at(Some(hi + 1)) << (WORD_BITS - bits)
+                    };
+                    at(Some(hi)) >> bits | carry
+                };
+            }
+        }
+        out.clear_tail();
+        out
+    }
+
+    /// How many of each cell's neighbours are set, for every cell at once. Uses the four
+    /// orthogonal neighbours, plus the four diagonal ones when `diagonals` is true.
+    pub fn neighbour_counts(&self, diagonals: bool) -> NeighbourCounts {
+        let mut counts = NeighbourCounts {
+            planes: std::array::from_fn(|_| BitGrid::new(self.rows, self.cols)),
+        };
+        for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            counts.add(self.shifted(dr, dc));
+        }
+        if diagonals {
+            for (dr, dc) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                counts.add(self.shifted(dr, dc));
+            }
+        }
+        counts
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                write!(f, "{}", if self.test(r, c) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-cell counts from [`BitGrid::neighbour_counts`], stored bit-sliced: plane `i` holds bit
+/// `i` of every cell's count.
+#[derive(Debug, Clone)]
+pub struct NeighbourCounts {
+    planes: [BitGrid; 4],
+}
+
+impl NeighbourCounts {
+    /// Adds one to every cell set in `grid`, as a ripple-carry adder across the planes.
+    fn add(&mut self, mut carry: BitGrid) {
+        for plane in &mut self.planes {
+            let mut next = plane.clone();
+            next.and_assign(&carry);
+            plane.xor_assign(&carry);
+            carry = next;
+        }
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> u8 {
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| u8::from(p.test(r, c)) << i)
+            .sum()
+    }
+
+    /// Cells whose count equals `k`. No count reaches 16, so a larger `k` matches nothing.
+    pub fn equal_to(&self, k: u8) -> BitGrid {
+        let mut out = self.planes[0].clone();
+        if k >> self.planes.len() != 0 {
+            out.data.fill(0);
+            return out;
+        }
+        for (i, w) in out.data.iter_mut().enumerate() {
+            *w = self.planes.iter().enumerate().fold(!0, |acc, (bit, p)| {
+                acc & if k >> bit & 1 == 1 {
+                    p.data[i]
+                } else {
+                    !p.data[i]
+                }
+            });
+        }
+        out.clear_tail();
+        out
+    }
+
+    /// Cells whose count is below `k`.
+    pub fn less_than(&self, k: u8) -> BitGrid {
+        let mut out = BitGrid::new(self.planes[0].rows, self.planes[0].cols);
+        for v in 0..k.min(9) {
+            out.or_assign(&self.equal_to(v));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(lines: &[&str]) -> BitGrid {
+        BitGrid::from_lines(lines, |c| c == '#').unwrap()
+    }
+
+    #[test]
+    fn cells_and_shifts() {
+        let mut g = BitGrid::new(3, 70);
+        g.set(0, 0);
+        g.set(1, 63);
+        g.set(2, 69);
+        assert_eq!(3, g.count_ones());
+        let ones = |g: &BitGrid| g.iter_ones().collect::<Vec<_>>();
+        assert_eq!(vec![(0, 0), (1, 63), (2, 69)], ones(&g));
+
+        assert_eq!(vec![(0, 1), (1, 64)], ones(&g.shifted(0, 1)));
+        assert_eq!(vec![(0, 0), (1, 6)], ones(&g.shifted(-1, -63)));
+        assert_eq!(vec![(0, 0)], ones(&g.shifted(0, 65).shifted(0, -65)));
+
+        g.toggle(0, 0);
+        g.clear(1, 63);
+        assert_eq!(vec![(2, 69)], ones(&g));
+        g.not_assign();
+        assert_eq!(3 * 70 - 1, g.count_ones());
+    }
+
+    #[test]
+    fn row_operations() {
+        let mut g = grid(&["##..", "..##"]);
+        let other = grid(&[".#.#", "####"]);
+        g.and_row_assign(0, other.row_words(0));
+        g.or_row_assign(1, other.row_words(0));
+        assert_eq!(grid(&[".#..", ".###"]), g);
+        g.xor_row_assign(1, other.row_words(1));
+        assert_eq!(grid(&[".#..", "#..."]), g);
+
+        // Bits beyond the last column never leak into the grid.
+        g.or_row_assign(0, &[!0]);
+        assert_eq!(4 + 1, g.count_ones());
+    }
+
+    #[test]
+    fn neighbours() {
+        let g = grid(&["##.", "#.#", "..#"]);
+        let counts = g.neighbour_counts(true);
+        assert_eq!(5, counts.get(1, 1));
+        assert_eq!(3, counts.get(2, 1));
+        assert_eq!(3, g.neighbour_counts(false).get(1, 1));
+        assert_eq!(grid(&["#.#", "#.#", "..."]), counts.equal_to(2));
+        assert_eq!(grid(&["...", ".#.", "..."]), counts.equal_to(5));
+        assert!(counts.equal_to(16).is_empty());
+        assert!(counts.equal_to(u8::MAX).is_empty());
+
+        let mut lonely = counts.less_than(2);
+        lonely.and_assign(&g);
+        assert_eq!(grid(&["...", "...", "..#"]), lonely);
+
+        assert!(BitGrid::from_lines(&["##", "#"], |c| c == '#').is_err());
+    }
+}
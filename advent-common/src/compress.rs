@@ -0,0 +1,156 @@
+//! Coordinate compression.
+//!
+//! A [`CompressedAxis`] splits a number line at a sorted set of breakpoints. Slot `i` stands for
+//! the half-open interval between breakpoints `i` and `i + 1`, so a handful of interesting
+//! coordinates spread over billions of values become a few dense indices. Grids and prefix sums
+//! built over those indices weight each slot by its width to get answers in the original space.
+
+use std::ops::Range;
+
+use crate::bitgrid::BitGrid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis {
+    bounds: Vec<i64>,
+}
+
+impl CompressedAxis {
+    /// Axis split at every given breakpoint. Values outside the first and last breakpoints
+    /// fall in no slot.
+    pub fn from_bounds(bounds: impl IntoIterator<Item = i64>) -> Self {
+        let mut bounds = bounds.into_iter().collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+        Self { bounds }
+    }
+
+    /// Axis where every given coordinate gets a slot of width one to itself, with the gaps
+    /// between them merged into wider slots.
+    pub fn from_points(points: impl IntoIterator<Item = i64>) -> Self {
+        Self::from_bounds(points.into_iter().flat_map(|p| [p, p + 1]))
+    }
+
+    /// Number of slots.
+    pub fn len(&self) -> usize {
+        self.bounds.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Slot starting exactly at breakpoint `coord`.
+    pub fn index(&self, coord: i64) -> Option<usize> {
+        self.bounds
+            .binary_search(&coord)
+            .ok()
+            .filter(|&i| i < self.len())
+    }
+
+    /// Slot whose interval contains `coord`.
+    pub fn locate(&self, coord: i64) -> Option<usize> {
+        let i = self.bounds.partition_point(|&b| b <= coord);
+        (1..=self.len()).contains(&i).then(|| i - 1)
+    }
+
+    /// First coordinate of slot `i`.
+    pub fn coord(&self, i: usize) -> i64 {
+        self.bounds[i]
+    }
+
+    /// Coordinates covered by slot `i`.
+    pub fn interval(&self, i: usize) -> Range<i64> {
+        self.bounds[i]..self.bounds[i + 1]
+    }
+
+    pub fn width(&self, i: usize) -> u64 {
+        self.bounds[i].abs_diff(self.bounds[i + 1])
+    }
+
+    /// Slots covering `range`, whose ends must both be breakpoints.
+    pub fn slots(&self, range: Range<i64>) -> Option<Range<usize>> {
+        let start = self.bounds.binary_search(&range.start).ok()?;
+        let end = self.bounds.binary_search(&range.end).ok()?;
+        Some(start..end)
+    }
+}
+
+/// Summed-area table over a compressed grid: the area, in original units, of the set cells in
+/// any block of slots.
+#[derive(Debug, Clone)]
+pub struct AreaTable {
+    cols: usize,
+    sums: Vec<u64>,
+}
+
+impl AreaTable {
+    pub fn new(grid: &BitGrid, rows: &CompressedAxis, cols: &CompressedAxis) -> Self {
+        assert!(
+            grid.rows() == rows.len() && grid.cols() == cols.len(),
+            "grid is {}x{} but the axes have {}x{} slots",
+            grid.rows(),
+            grid.cols(),
+            rows.len(),
+            cols.len()
+        );
+        let stride = cols.len() + 1;
+        let mut sums = vec![0; (rows.len() + 1) * stride];
+        for r in 0..rows.len() {
+            for c in 0..cols.len() {
+                let area = if grid.test(r, c) {
+                    rows.width(r) * cols.width(c)
+                } else {
+                    0
+                };
+                sums[(r + 1) * stride + c + 1] =
+                    area + sums[r * stride + c + 1] + sums[(r + 1) * stride + c]
+                        - sums[r * stride + c];
+            }
+        }
+        Self { cols: stride, sums }
+    }
+
+    /// Area of the set cells with row slot in `rows` and column slot in `cols`.
+    pub fn area(&self, rows: Range<usize>, cols: Range<usize>) -> u64 {
+        let at = |r: usize, c: usize| self.sums[r * self.cols + c];
+        at(rows.end, cols.end) + at(rows.start, cols.start)
+            - at(rows.start, cols.end)
+            - at(rows.end, cols.start)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn axis() {
+        let axis = CompressedAxis::from_points([1_000_000_000, 5, 5, 12]);
+        assert_eq!(5, axis.len());
+        assert_eq!(Some(2), axis.index(12));
+        assert_eq!(None, axis.index(7));
+        assert_eq!(Some(1), axis.locate(11));
+        assert_eq!(Some(4), axis.locate(1_000_000_000));
+        assert_eq!(None, axis.locate(1_000_000_001));
+        assert_eq!(None, axis.locate(4));
+        assert_eq!(6, axis.width(1));
+        assert_eq!(13..1_000_000_000, axis.interval(3));
+        assert_eq!(Some(1..3), axis.slots(6..13));
+        assert_eq!(None, axis.slots(7..13));
+    }
+
+    #[test]
+    fn area_table() {
+        let rows = CompressedAxis::from_bounds([0, 1, 10]);
+        let cols = CompressedAxis::from_bounds([0, 100, 101, 200]);
+        let mut grid = BitGrid::new(rows.len(), cols.len());
+        grid.set(0, 0);
+        grid.set(1, 1);
+        grid.set(1, 2);
+
+        let table = AreaTable::new(&grid, &rows, &cols);
+        assert_eq!(100 + 9 + 9 * 99, table.area(0..2, 0..3));
+        assert_eq!(9 + 9 * 99, table.area(1..2, 1..3));
+        assert_eq!(0, table.area(0..1, 1..3));
+    }
+}
@@ -0,0 +1,189 @@
+//! Shared helpers to find where a deterministic simulation starts repeating itself.
+//!
+//! Every function takes a start state and a `step` function. It describes the resulting sequence
+//! `x0, x1 = step(x0), ...` as a [`CycleInfo`]: `prefix` states before the loop starts and a loop
+//! of `period` states.
+//!
+//! - [`floyd`] and [`brent`] only keep a couple of states alive, for large or cheap-to-step
+//!   states. Brent's variant needs fewer `step` calls.
+//! - [`find_cycle`] hashes every state it sees. It finds the cycle in a single pass and also
+//!   handles sequences that terminate.
+//! - [`simulate_until`] jumps straight to step `n` once the cycle is known.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Shape of an eventually periodic sequence: `x[i + period] == x[i]` for every `i >= prefix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CycleInfo {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl CycleInfo {
+    /// The earliest step with the same state as step `n`.
+    pub fn reduce(&self, n: u64) -> u64 {
+        let prefix = self.prefix as u64;
+        if n < prefix {
+            n
+        } else {
+            prefix + (n - prefix) % self.period as u64
+        }
+    }
+}
+
+/// Floyd's tortoise and hare. Never returns if the sequence does not repeat.
+pub fn floyd<T: PartialEq>(start: T, mut step: impl FnMut(&T) -> T) -> CycleInfo {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let half = step(&hare);
+        hare = step(&half);
+    }
+
+    // The meeting point is a multiple of the period away from the start, so walking one pointer
+    // from the start and one from the meeting point lines them up at the first repeated state.
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    CycleInfo { prefix, period }
+}
+
+/// Brent's power-of-two search. Never returns if the sequence does not repeat.
+pub fn brent<T: PartialEq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> CycleInfo {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    CycleInfo { prefix, period }
+}
+
+/// Walks the sequence until a state repeats, remembering every state seen.
+///
+/// `step` returns `None` when the simulation ends, in which case there is no cycle.
+pub fn find_cycle<T: Hash + Eq>(
+    start: T,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<CycleInfo> {
+    let mut seen = HashMap::new();
+    let mut state = start;
+    for i in 0.. {
+        if let Some(&first) = seen.get(&state) {
+            return Some(CycleInfo {
+                prefix: first,
+                period: i - first,
+            });
+        }
+        let next = step(&state)?;
+        seen.insert(state, i);
+        state = next;
+    }
+    unreachable!()
+}
+
+/// State after `n` steps, skipping whole periods once the sequence starts repeating.
+///
+/// Costs at most `prefix + period` steps however large `n` is.
+pub fn simulate_until<T: Hash + Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T, n: u64) -> T {
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = start;
+    for i in 0.. {
+        if i == n {
+            return state;
+        }
+        if let Some(&first) = seen.get(&state) {
+            let cycle = CycleInfo {
+                prefix: first,
+                period: i as usize - first,
+            };
+            return history.swap_remove(cycle.reduce(n) as usize);
+        }
+        seen.insert(state.clone(), i as usize);
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 0, 1, ..., 4 then loops over 5..=11.
+    fn rho(x: &u32) -> u32 {
+        if *x == 11 { 5 } else { x + 1 }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = CycleInfo {
+            prefix: 5,
+            period: 7,
+        };
+        assert_eq!(expected, floyd(0, rho));
+        assert_eq!(expected, brent(0, rho));
+        assert_eq!(Some(expected), find_cycle(0, |x| Some(rho(x))));
+        assert_eq!(None, find_cycle(0, |&x| (x < 3).then_some(x + 1)));
+
+        let pure = CycleInfo {
+            prefix: 0,
+            period: 7,
+        };
+        assert_eq!(pure, floyd(5, rho));
+        assert_eq!(pure, brent(5, rho));
+    }
+
+    #[test]
+    fn simulate_far_ahead() {
+        assert_eq!(3, simulate_until(0, rho, 3));
+        assert_eq!(5, simulate_until(0, rho, 12));
+        // 10^12 - 5 ≡ 3 (mod 7)
+        assert_eq!(8, simulate_until(0, rho, 1_000_000_000_000));
+        assert_eq!(
+            8,
+            CycleInfo {
+                prefix: 5,
+                period: 7
+            }
+            .reduce(1_000_000_000_000)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Decimal digit helpers for the unsigned integer widths puzzles use.
+//!
+//! Everything goes through a per-type table of powers of ten, so counting and splitting digits
+//! never formats the number as a string. Operations that could overflow return `Option`.
+
+/// Decimal digit operations, implemented for `u32`, `u64` and `u128`.
+pub trait Digits: Copy + Ord + Sized + 'static {
+    /// `10^0, 10^1, ...` up to the largest power that fits.
+    const POW10: &'static [Self];
+
+    /// `10^exp`, if it fits.
+    fn pow10(exp: u32) -> Option<Self> {
+        Self::POW10.get(exp as usize).copied()
+    }
+
+    /// Number of decimal digits, with `0` having one.
+    fn num_digits(self) -> u32 {
+        (Self::POW10.partition_point(|&p| p <= self) as u32).max(1)
+    }
+
+    /// `(high, low)` where `low` holds the last `k` digits, so `split_at(1234, 1)` is
+    /// `(123, 4)`.
+    fn split_at(self, k: u32) -> (Self, Self);
+
+    /// The digits of `self` followed by those of `other`, if the result fits.
+    fn concat(self, other: Self) -> Option<Self>;
+
+    /// `block` written `times` times in a row, e.g. `12` three times is `121212`.
+    fn repeat_block(block: Self, times: u32) -> Option<Self>;
+
+    /// The digits from most to least significant.
+    fn digits(self) -> impl Iterator<Item = u8>;
+}
+
+macro_rules! impl_digits {
+    ($($t:ty => $powers:expr),* $(,)?) => {$(
+        impl Digits for $t {
+            const POW10: &'static [$t] = &{
+                let mut table = [1 as $t; $powers];
+                let mut i = 1;
+                while i < $powers {
+                    table[i] = table[i - 1] * 10;
+                    i += 1;
+                }
+                table
+            };
+
+            fn split_at(self, k: u32) -> (Self, Self) {
+                match Self::pow10(k) {
+                    Some(p) => (self / p, self % p),
+                    None => (0, self),
+                }
+            }
+
+            fn concat(self, other: Self) -> Option<Self> {
+                // The shift may not fit even when nothing is shifted.
+                if self == 0 {
+                    return Some(other);
+                }
+                self.checked_mul(Self::pow10(other.num_digits())?)?
+                    .checked_add(other)
+            }
+
+            fn repeat_block(block: Self, times: u32) -> Option<Self> {
+                match times {
+                    0 => return None,
+                    1 => return Some(block),
+                    _ => {}
+                }
+                let shift = Self::pow10(block.num_digits())?;
+                (1..times).try_fold(block, |n, _| n.checked_mul(shift)?.checked_add(block))
+            }
+
+            fn digits(self) -> impl Iterator<Item = u8> {
+                (0..self.num_digits())
+                    .rev()
+                    .map(move |k| (self / Self::POW10[k as usize] % 10) as u8)
+            }
+        }
+    )*};
+}
+
+impl_digits!(u32 => 10, u64 => 20, u128 => 39);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counting_and_splitting() {
+        assert_eq!(1, 0u32.num_digits());
+        assert_eq!(1, 9u64.num_digits());
+        assert_eq!(2, 10u64.num_digits());
+        assert_eq!(10, u32::MAX.num_digits());
+        assert_eq!(20, u64::MAX.num_digits());
+        assert_eq!(39, u128::MAX.num_digits());
+        assert_eq!(Some(10u64.pow(19)), u64::pow10(19));
+        assert_eq!(None, u64::pow10(20));
+
+        assert_eq!((123, 4), 1234u64.split_at(1));
+        assert_eq!((10, 0), 1000u32.split_at(2));
+        assert_eq!((0, 77), 77u32.split_at(12));
+        assert_eq!(vec![4, 0, 9], 409u128.digits().collect::<Vec<_>>());
+        assert_eq!(vec![0], 0u64.digits().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn building() {
+        assert_eq!(Some(12345), 12u64.concat(345));
+        assert_eq!(Some(10), 1u32.concat(0));
+        assert_eq!(None, 4_294_967u32.concat(296));
+        assert_eq!(Some(121212), u64::repeat_block(12, 3));
+        assert_eq!(Some(7), u32::repeat_block(7, 1));
+        assert_eq!(None, u32::repeat_block(7, 0));
+        assert_eq!(Some(1_234_512_345), u32::repeat_block(12345, 2));
+        assert_eq!(None, u32::repeat_block(54321, 2));
+
+        // 10^19 has 20 digits, and 10^20 does not fit, but nothing needs shifting.
+        let big = 10u64.pow(19);
+        assert_eq!(Some(big), 0u64.concat(big));
+        assert_eq!(None, 1u64.concat(big));
+        assert_eq!(Some(big), u64::repeat_block(big, 1));
+        assert_eq!(None, u64::repeat_block(big, 2));
+    }
+}
@@ -0,0 +1,360 @@
+//! Exact cover with Knuth's [Algorithm X](https://en.wikipedia.org/wiki/Knuth%27s_Algorithm_X),
+//! implemented with dancing links.
+//!
+//! A problem has *primary* columns, which every solution must cover exactly once, and
+//! *secondary* columns, which may be covered at most once. Each row is a set of columns, and a
+//! solution is a set of rows that satisfies every column. Packing puzzles map onto this
+//! directly: pieces are primary columns, grid cells are secondary ones when cells may stay
+//! empty, and each placement of a piece is a row.
+//!
+//! Identical pieces make the search revisit every permutation of the same packing. Marking their
+//! columns with [`ExactCover::interchangeable`] makes copies take their rows in increasing order,
+//! so each packing is found once.
+//!
+//! All links live in flat index vectors. Node 0 is the root, nodes `1..=columns` are the column
+//! headers and the rest are row entries.
+
+/// Exact-cover problem under construction or being searched.
+#[derive(Debug, Clone)]
+pub struct ExactCover {
+    primary: usize,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    /// Index of each row entry among the rows of its column, in insertion order.
+    position: Vec<usize>,
+    /// For a header in an interchangeable group, the header of the previous copy.
+    previous_copy: Vec<Option<usize>>,
+    /// Whether each header is covered by a row of the current partial solution.
+    used: Vec<bool>,
+    /// Position of the row chosen while branching on each header, if it was covered that way.
+    branched_at: Vec<Option<usize>>,
+    rows: usize,
+}
+
+impl ExactCover {
+    /// Columns `0..primary` are primary and `primary..primary + secondary` are secondary.
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let headers = primary + secondary + 1;
+        let mut dlx = Self {
+            primary,
+            left: (0..headers).collect(),
+            right: (0..headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            position: vec![0; headers],
+            previous_copy: vec![None; headers],
+            used: vec![false; headers],
+            branched_at: vec![None; headers],
+            rows: 0,
+        };
+
+        // Only primary headers join the root's list, so the search never has to pick a
+        // secondary column.
+        for h in 0..=primary {
+            dlx.left[h] = if h == 0 { primary } else { h - 1 };
+            dlx.right[h] = if h == primary { 0 } else { h + 1 };
+        }
+        dlx
+    }
+
+    pub fn columns(&self) -> usize {
+        self.size.len() - 1
+    }
+
+    pub fn primary_columns(&self) -> usize {
+        self.primary
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Adds a row covering `columns` and returns its index. Rows are numbered from 0 in the
+    /// order they are added.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        assert!(!columns.is_empty(), "rows must cover at least one column");
+        let r = self.rows;
+        self.rows += 1;
+
+        let first = self.left.len();
+        for (i, &c) in columns.iter().enumerate() {
+            assert!(c < self.columns(), "column {c} out of range");
+            let header = c + 1;
+            let node = first + i;
+            self.left.push(if i == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if i + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.row.push(r);
+            self.position.push(self.size[header]);
+
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+        r
+    }
+
+    /// Declares the primary `columns` to be copies of one piece: each must have the same rows,
+    /// added in the same order, and a row may cover at most one of them. Rows that use a copy
+    /// are only tried once the copies before it are used, and a copy picked by branching on it
+    /// must take a later row than the previous copy did, so solutions that only differ by
+    /// swapping copies are reported once.
+    pub fn interchangeable(&mut self, columns: std::ops::Range<usize>) {
+        assert!(
+            columns.end <= self.primary,
+            "only primary columns can be interchangeable"
+        );
+        for c in columns.start + 1..columns.end {
+            self.previous_copy[c + 1] = Some(c);
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+    }
+
+    /// Whether row entry `r` may be chosen while branching on column `c`, given the ordering
+    /// rules for interchangeable copies.
+    fn admissible(&self, c: usize, r: usize) -> bool {
+        let mut j = r;
+        loop {
+            let x = self.column[j];
+            if let Some(p) = self.previous_copy[x] {
+                if !self.used[p] {
+                    return false;
+                }
+                if x == c && self.branched_at[p].is_some_and(|q| self.position[j] <= q) {
+                    return false;
+                }
+            }
+            j = self.right[j];
+            if j == r {
+                return true;
+            }
+        }
+    }
+
+    /// Algorithm X, branching on the primary column with the fewest remaining rows. Returns
+    /// true once `visit` asks to stop.
+    fn search(
+        &mut self,
+        partial: &mut Vec<usize>,
+        visit: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if self.right[0] == 0 {
+            return !visit(partial);
+        }
+
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+        while j != 0 {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        while let Some(p) = self.previous_copy[c].filter(|&p| !self.used[p]) {
+            c = p;
+        }
+        if self.size[c] == 0 {
+            return false;
+        }
+
+        self.cover(c);
+        let mut r = self.down[c];
+        let mut stop = false;
+        while r != c && !stop {
+            if !self.admissible(c, r) {
+                r = self.down[r];
+                continue;
+            }
+
+            self.branched_at[c] = Some(self.position[r]);
+            partial.push(self.row[r]);
+            let mut j = r;
+            loop {
+                self.used[self.column[j]] = true;
+                if j != r {
+                    self.cover(self.column[j]);
+                }
+                j = self.right[j];
+                if j == r {
+                    break;
+                }
+            }
+
+            stop = self.search(partial, visit);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                self.used[self.column[j]] = false;
+                j = self.left[j];
+            }
+            self.used[c] = false;
+            partial.pop();
+            r = self.down[r];
+        }
+        self.branched_at[c] = None;
+        self.uncover(c);
+        stop
+    }
+
+    /// Calls `visit` with the rows of each solution until it returns false.
+    pub fn for_each_solution(&mut self, mut visit: impl FnMut(&[usize]) -> bool) {
+        self.search(&mut Vec::new(), &mut visit);
+    }
+
+    /// Rows of some solution, if there is one.
+    pub fn first_solution(&mut self) -> Option<Vec<usize>> {
+        let mut found = None;
+        self.for_each_solution(|rows| {
+            found = Some(rows.to_vec());
+            false
+        });
+        found
+    }
+
+    pub fn count_solutions(&mut self) -> u64 {
+        let mut count = 0;
+        self.for_each_solution(|_| {
+            count += 1;
+            true
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn knuth_example() {
+        // Columns A..G from Knuth's "Dancing Links" paper.
+        let mut dlx = ExactCover::new(7, 0);
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            dlx.add_row(row);
+        }
+
+        let mut solution = dlx.first_solution().unwrap();
+        solution.sort();
+        assert_eq!(vec![0, 3, 4], solution);
+        assert_eq!(1, dlx.count_solutions());
+    }
+
+    #[test]
+    fn secondary_columns() {
+        // Two dominoes in a 1x3 strip: cells are secondary, so one cell may stay empty.
+        let mut dlx = ExactCover::new(2, 3);
+        for piece in 0..2 {
+            for start in 0..2 {
+                dlx.add_row(&[piece, 2 + start, 3 + start]);
+            }
+        }
+        assert_eq!(0, dlx.count_solutions());
+
+        // A domino and a monomino fit, with the monomino at either end.
+        let mut dlx = ExactCover::new(2, 3);
+        for start in 0..2 {
+            dlx.add_row(&[0, 2 + start, 3 + start]);
+        }
+        for cell in 0..3 {
+            dlx.add_row(&[1, 2 + cell]);
+        }
+        assert_eq!(2, dlx.count_solutions());
+    }
+
+    #[test]
+    fn interchangeable_copies() {
+        // Two monominoes in a 1x3 strip: 6 ordered placements, 3 once the copies are identical.
+        let build = || {
+            let mut dlx = ExactCover::new(2, 3);
+            for piece in 0..2 {
+                for cell in 0..3 {
+                    dlx.add_row(&[piece, 2 + cell]);
+                }
+            }
+            dlx
+        };
+        assert_eq!(6, build().count_solutions());
+
+        let mut dlx = build();
+        dlx.interchangeable(0..2);
+        assert_eq!(3, dlx.count_solutions());
+
+        // Two dominoes and a hole filling a 1x5 strip, with every cell primary so the search
+        // also branches on cells: the hole can only go at 0, 2 or 4.
+        let mut dlx = ExactCover::new(8, 0);
+        dlx.interchangeable(0..2);
+        for piece in 0..2 {
+            for start in 0..4 {
+                dlx.add_row(&[piece, 3 + start, 4 + start]);
+            }
+        }
+        for cell in 0..5 {
+            dlx.add_row(&[2, 3 + cell]);
+        }
+        assert_eq!(3, dlx.count_solutions());
+    }
+}
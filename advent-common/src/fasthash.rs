@@ -0,0 +1,116 @@
+//! A fast, non-cryptographic hasher for the small keys puzzles use.
+//!
+//! The default `HashMap` hasher, SipHash, resists collision attacks that puzzle inputs never
+//! mount, and its per-key setup shows up in hot loops over integers, tuples and short names.
+//! [`FxHasher`] is the multiply-rotate hash used inside rustc: a few instructions per word.
+//! [`FastMap`] and [`FastSet`] are the std collections using it, built with `default()`.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+pub type FastBuildHasher = BuildHasherDefault<FxHasher>;
+pub type FastMap<K, V> = HashMap<K, V, FastBuildHasher>;
+pub type FastSet<T> = HashSet<T, FastBuildHasher>;
+
+/// Folds each word in with a rotate, xor and multiply.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        // Mixing in the length keeps "a" and "a\0" apart.
+        self.add(bytes.len() as u64);
+        let mut rest = bytes;
+        while let Some((word, tail)) = rest.split_first_chunk::<8>() {
+            self.add(u64::from_le_bytes(*word));
+            rest = tail;
+        }
+        if let Some((word, tail)) = rest.split_first_chunk::<4>() {
+            self.add(u32::from_le_bytes(*word) as u64);
+            rest = tail;
+        }
+        if let Some((word, tail)) = rest.split_first_chunk::<2>() {
+            self.add(u16::from_le_bytes(*word) as u64);
+            rest = tail;
+        }
+        if let Some(&byte) = rest.first() {
+            self.add(byte as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        // The multiply mixes best into the high bits, but tables pick buckets from the low ones.
+        self.hash.rotate_left(26)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::{BuildHasher, Hash};
+
+    use super::*;
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        FastBuildHasher::default().hash_one(value)
+    }
+
+    #[test]
+    fn distinguishes_keys() {
+        assert_eq!(hash(&(1i64, -2i64)), hash(&(1i64, -2i64)));
+        assert_ne!(hash(&(1i64, -2i64)), hash(&(-2i64, 1i64)));
+        assert_ne!(hash("a"), hash("a\0"));
+        assert_ne!(hash("abcdefgh"), hash("abcdefghi"));
+
+        let set = (0..10_000u32).map(|i| hash(&i)).collect::<FastSet<_>>();
+        assert_eq!(10_000, set.len());
+    }
+
+    #[test]
+    fn collections() {
+        let mut counts = FastMap::default();
+        for word in "the cat and the hat and the bat".split(' ') {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+        assert_eq!(Some(&3), counts.get("the"));
+        assert_eq!(Some(&2), counts.get("and"));
+        assert_eq!(None, counts.get("dog"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Lattice polygons, with extra queries for rectilinear ones.
+//!
+//! Vertices are integer `(x, y)` points listed in order around the polygon, either way round.
+//! Everything works on the edges alone, so coordinates can be in the billions without the
+//! polygon ever being drawn cell by cell. For many queries against one polygon,
+//! [`RectilinearPolygon::rasterize`] draws it on a compressed grid instead.
+
+use crate::AdventError;
+use crate::bitgrid::BitGrid;
+use crate::compress::CompressedAxis;
+
+pub type Point = (i64, i64);
+
+/// Twice the signed area enclosed by `vertices` (the shoelace formula). Positive when the
+/// vertices run counter-clockwise in a y-up frame.
+pub fn shoelace_area2(vertices: &[Point]) -> i64 {
+    closed_edges(vertices)
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum()
+}
+
+/// Lattice points lying on the polygon's edges.
+pub fn boundary_points(vertices: &[Point]) -> u64 {
+    closed_edges(vertices)
+        .map(|((x1, y1), (x2, y2))| gcd(x1.abs_diff(x2), y1.abs_diff(y2)))
+        .sum()
+}
+
+/// Lattice points strictly inside a polygon, by Pick's theorem `A = I + B/2 - 1`.
+///
+/// `None` when the area is too small for the boundary, which no simple polygon allows.
+pub fn pick_interior(area2: i64, boundary: u64) -> Option<u64> {
+    (area2.unsigned_abs() + 2)
+        .checked_sub(boundary)
+        .map(|twice| twice / 2)
+}
+
+fn closed_edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// A simple polygon whose edges are all horizontal or vertical.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point>,
+}
+
+impl RectilinearPolygon {
+    pub fn new(vertices: Vec<Point>) -> Result<Self, AdventError> {
+        if vertices.len() < 4 {
+            return Err(AdventError::InputParseError(
+                format!("polygon needs at least 4 vertices, got {}", vertices.len()).into(),
+            ));
+        }
+        if let Some((a, b)) = closed_edges(&vertices).find(|(a, b)| a.0 != b.0 && a.1 != b.1) {
+            return Err(AdventError::InputParseError(
+                format!("edge {a:?} -> {b:?} is not horizontal or vertical").into(),
+            ));
+        }
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        closed_edges(&self.vertices)
+    }
+
+    /// Twice the enclosed area, always non-negative.
+    pub fn area2(&self) -> u64 {
+        shoelace_area2(&self.vertices).unsigned_abs()
+    }
+
+    /// Lattice points on or inside the polygon, i.e. the grid cells it covers when vertices are
+    /// cell centres. `None` if the edges cross or double back, so Pick's theorem does not hold.
+    pub fn lattice_points(&self) -> Option<u64> {
+        let boundary = boundary_points(&self.vertices);
+        Some(pick_interior(self.area2() as i64, boundary)? + boundary)
+    }
+
+    /// Whether `p` is inside the polygon or on its boundary.
+    pub fn contains(&self, p: Point) -> bool {
+        self.contains_doubled((2 * p.0, 2 * p.1))
+    }
+
+    /// [`Self::contains`] for a point given in half units, so midpoints stay exact.
+    fn contains_doubled(&self, (px, py): Point) -> bool {
+        let mut inside = false;
+        for ((x1, y1), (x2, y2)) in self.edges() {
+            let (x1, y1, x2, y2) = (2 * x1, 2 * y1, 2 * x2, 2 * y2);
+            let (lo_x, hi_x) = (x1.min(x2), x1.max(x2));
+            let (lo_y, hi_y) = (y1.min(y2), y1.max(y2));
+            if (lo_x..=hi_x).contains(&px) && (lo_y..=hi_y).contains(&py) {
+                return true;
+            }
+            // Cast a ray towards +x; counting vertical edges over the half-open span
+            // `lo_y..hi_y` counts a ray through a vertex exactly once.
+            if x1 == x2 && x1 > px && (lo_y..hi_y).contains(&py) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Whether the axis-aligned rectangle with opposite corners `a` and `b` lies entirely on
+    /// or inside the polygon.
+    pub fn contains_rect(&self, a: Point, b: Point) -> bool {
+        let (x1, x2) = (a.0.min(b.0), a.0.max(b.0));
+        let (y1, y2) = (a.1.min(b.1), a.1.max(b.1));
+        if x1 == x2 || y1 == y2 {
+            return self.contains_segment((x1, y1), (x2, y2));
+        }
+
+        // With no edge passing through its open interior, the rectangle is either wholly
+        // inside or wholly outside, and its centre tells which.
+        let crosses = self.edges().any(|((ex1, ey1), (ex2, ey2))| {
+            let (lo_x, hi_x) = (ex1.min(ex2), ex1.max(ex2));
+            let (lo_y, hi_y) = (ey1.min(ey2), ey1.max(ey2));
+            if lo_x == hi_x {
+                x1 < lo_x && lo_x < x2 && lo_y.max(y1) < hi_y.min(y2)
+            } else {
+                y1 < lo_y && lo_y < y2 && lo_x.max(x1) < hi_x.min(x2)
+            }
+        });
+        !crosses && self.contains_doubled((x1 + x2, y1 + y2))
+    }
+
+    /// Marks the compressed cells on or inside the polygon. Every vertex coordinate must be a
+    /// breakpoint of its axis, as with [`CompressedAxis::from_points`], so that each cell is
+    /// either wholly inside or wholly outside.
+    ///
+    /// Each row is swept left to right, flipping between outside and inside at every vertical
+    /// edge that crosses it, and then the edges themselves are drawn on top.
+    ///
+    /// # Panics
+    ///
+    /// If a vertex coordinate does not start a slot of its axis.
+    pub fn rasterize(&self, rows: &CompressedAxis, cols: &CompressedAxis) -> BitGrid {
+        let mut grid = BitGrid::new(rows.len(), cols.len());
+        for r in 0..rows.len() {
+            let y = rows.coord(r);
+            let mut crossings = self
+                .edges()
+                .filter(|(a, b)| a.0 == b.0 && (a.1.min(b.1)..a.1.max(b.1)).contains(&y))
+                .map(|(a, _)| a.0)
+                .collect::<Vec<_>>();
+            crossings.sort_unstable();
+
+            let mut passed = 0;
+            for c in 0..cols.len() {
+                let x = cols.coord(c);
+                while passed < crossings.len() && crossings[passed] <= x {
+                    passed += 1;
+                }
+                grid.assign(r, c, passed % 2 == 1);
+            }
+        }
+
+        let slot = |axis: &CompressedAxis, v: i64| {
+            axis.index(v)
+                .unwrap_or_else(|| panic!("vertex coordinate {v} does not start a slot"))
+        };
+        for (a, b) in self.edges() {
+            let (r1, r2) = (slot(rows, a.1.min(b.1)), slot(rows, a.1.max(b.1)));
+            let (c1, c2) = (slot(cols, a.0.min(b.0)), slot(cols, a.0.max(b.0)));
+            for r in r1..=r2 {
+                for c in c1..=c2 {
+                    grid.set(r, c);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Whether the horizontal or vertical segment `a..=b` lies on or inside the polygon. Only
+    /// the ends and the middle of each stretch between edges crossing it need checking.
+    fn contains_segment(&self, a: Point, b: Point) -> bool {
+        let vertical = a.0 == b.0;
+        let along = |p: Point| if vertical { p.1 } else { p.0 };
+        let across = |p: Point| if vertical { p.0 } else { p.1 };
+
+        let (start, end) = (along(a), along(b));
+        let mut cuts = vec![start, end];
+        for (e1, e2) in self.edges() {
+            let (lo, hi) = (across(e1).min(across(e2)), across(e1).max(across(e2)));
+            let at = along(e1);
+            if across(e1) != across(e2) && (lo..=hi).contains(&across(a)) && start < at && at < end
+            {
+                cuts.push(at);
+            }
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let point = |t: i64| {
+            if vertical { (2 * a.0, t) } else { (t, 2 * a.1) }
+        };
+        cuts.iter().all(|&t| self.contains_doubled(point(2 * t)))
+            && cuts
+                .windows(2)
+                .all(|w| self.contains_doubled(point(w[0] + w[1])))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> RectilinearPolygon {
+        RectilinearPolygon::new(vec![
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn area_and_pick() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(32, shoelace_area2(&square));
+        assert_eq!(-32, shoelace_area2(&[(0, 0), (0, 4), (4, 4), (4, 0)]));
+        assert_eq!(16, boundary_points(&square));
+        assert_eq!(Some(9), pick_interior(32, 16));
+
+        let triangle = [(0, 0), (4, 0), (0, 2)];
+        assert_eq!(8, boundary_points(&triangle));
+        assert_eq!(Some(1), pick_interior(shoelace_area2(&triangle), 8));
+
+        let unit = RectilinearPolygon::new(vec![(0, 0), (1, 0), (1, 1), (0, 1)]).unwrap();
+        assert_eq!(Some(0), pick_interior(2, 4));
+        assert_eq!(Some(4), unit.lattice_points());
+        let flat = RectilinearPolygon::new(vec![(0, 0), (3, 0), (3, 0), (0, 0)]).unwrap();
+        assert_eq!(None, flat.lattice_points());
+
+        assert_eq!(Some(46), sample().lattice_points());
+        assert!(RectilinearPolygon::new(vec![(0, 0), (2, 0), (2, 2), (1, 3)]).is_err());
+    }
+
+    #[test]
+    fn containment() {
+        let p = sample();
+        assert!(p.contains((7, 1)));
+        assert!(p.contains((10, 4)));
+        assert!(p.contains((3, 4)));
+        assert!(!p.contains((3, 2)));
+        assert!(!p.contains((10, 8)));
+        assert!(!p.contains((0, 3)));
+
+        assert!(p.contains_rect((9, 5), (2, 3)));
+        assert!(p.contains_rect((7, 1), (11, 5)));
+        assert!(!p.contains_rect((2, 5), (11, 1)));
+        assert!(!p.contains_rect((2, 3), (11, 7)));
+        assert!(p.contains_rect((9, 7), (11, 7)));
+        assert!(p.contains_rect((2, 5), (11, 5)));
+        assert!(p.contains_rect((2, 3), (11, 3)));
+        assert!(!p.contains_rect((2, 6), (11, 6)));
+        assert!(!p.contains_rect((3, 1), (3, 5)));
+    }
+
+    #[test]
+    fn rasterize() {
+        let p = sample();
+        let cols = CompressedAxis::from_points(p.vertices().iter().map(|v| v.0));
+        let rows = CompressedAxis::from_points(p.vertices().iter().map(|v| v.1));
+        let grid = p.rasterize(&rows, &cols);
+        assert_eq!((rows.len(), cols.len()), (grid.rows(), grid.cols()));
+        for r in 0..rows.len() {
+            for c in 0..cols.len() {
+                assert_eq!(p.contains((cols.coord(c), rows.coord(r))), grid.test(r, c));
+            }
+        }
+        // The slot for x = 3 lies in the gap between the vertex columns 2 and 7.
+        assert!(!grid.test(rows.locate(2).unwrap(), cols.locate(3).unwrap()));
+        assert!(grid.test(rows.locate(4).unwrap(), cols.locate(3).unwrap()));
+    }
+}
@@ -0,0 +1,631 @@
+//! Bit-packed linear algebra over [GF(2)](https://en.wikipedia.org/wiki/GF(2)).
+//!
+//! Rows are stored as `u64` words so that row additions (XOR) touch `cols / 64` words at a time.
+//! This is the natural model for "toggle" puzzles where pressing a button flips a fixed set of
+//! lights: pressing twice is a no-op, so each button is either pressed or not.
+
+use std::fmt;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+fn words_for(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+/// Fixed-length vector over GF(2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Vector {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl Gf2Vector {
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            len,
+            words: vec![0; words_for(len)],
+        }
+    }
+
+    pub fn from_bools(bits: &[bool]) -> Self {
+        let mut v = Self::zeros(bits.len());
+        for (i, &b) in bits.iter().enumerate() {
+            v.set(i, b);
+        }
+        v
+    }
+
+    /// The low `len` bits of `value`, bit `i` becoming element `i`.
+    pub fn from_bits(value: u64, len: usize) -> Self {
+        assert!(len <= WORD_BITS, "{len} bits do not fit in a u64");
+        let mut v = Self::zeros(len);
+        if len > 0 {
+            v.words[0] = value & (u64::MAX >> (WORD_BITS - len));
+        }
+        v
+    }
+
+    /// Inverse of [`Gf2Vector::from_bits`].
+    pub fn to_bits(&self) -> u64 {
+        assert!(
+            self.len <= WORD_BITS,
+            "{} bits do not fit in a u64",
+            self.len
+        );
+        self.words.first().copied().unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {i} out of range for length {}", self.len);
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {i} out of range for length {}", self.len);
+        let mask = 1 << (i % WORD_BITS);
+        if value {
+            self.words[i / WORD_BITS] |= mask;
+        } else {
+            self.words[i / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn toggle(&mut self, i: usize) {
+        assert!(i < self.len, "bit {i} out of range for length {}", self.len);
+        self.words[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    /// In-place vector addition, which over GF(2) is XOR.
+    pub fn xor_assign(&mut self, other: &Gf2Vector) {
+        assert_eq!(self.len, other.len, "vector length mismatch");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+
+    /// Dot product, i.e. the parity of the bitwise AND.
+    pub fn dot(&self, other: &Gf2Vector) -> bool {
+        assert_eq!(self.len, other.len, "vector length mismatch");
+        self.words
+            .iter()
+            .zip(&other.words)
+            .fold(0, |acc, (a, b)| acc ^ (a & b).count_ones())
+            & 1
+            == 1
+    }
+
+    /// Hamming weight (number of set bits).
+    pub fn weight(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Indices of the set bits in ascending order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(wi, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit = w.trailing_zeros() as usize;
+                w &= w - 1;
+                Some(wi * WORD_BITS + bit)
+            })
+        })
+    }
+
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+impl fmt::Display for Gf2Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..self.len {
+            write!(f, "{}", if self.get(i) { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+/// Dense `rows × cols` matrix over GF(2), stored row-major with each row padded to whole words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Matrix {
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    data: Vec<u64>,
+}
+
+impl Gf2Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        let stride = words_for(cols);
+        Self {
+            rows,
+            cols,
+            stride,
+            data: vec![0; rows * stride],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m.set(i, i, true);
+        }
+        m
+    }
+
+    /// Builds a matrix from its columns, e.g. one column per button listing the lights it toggles.
+    pub fn from_columns<I, C>(rows: usize, columns: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoIterator<Item = usize>,
+    {
+        let columns = columns
+            .into_iter()
+            .map(|c| c.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut m = Self::zeros(rows, columns.len());
+        for (c, col) in columns.iter().enumerate() {
+            for &r in col {
+                m.toggle(r, c);
+            }
+        }
+        m
+    }
+
+    /// The `bits` x `bits` matrix of a function on `bits`-bit words that is linear over GF(2),
+    /// i.e. built from XORs, shifts and masks. Column `j` is `f(1 << j)`.
+    pub fn from_linear_fn(bits: usize, f: impl Fn(u64) -> u64) -> Self {
+        assert!(bits <= WORD_BITS, "{bits} bits do not fit in a u64");
+        let mut m = Self::zeros(bits, bits);
+        for c in 0..bits {
+            let image = Gf2Vector::from_bits(f(1 << c), bits);
+            for r in image.ones() {
+                m.set(r, c, true);
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn row_words(&self, r: usize) -> &[u64] {
+        &self.data[r * self.stride..(r + 1) * self.stride]
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        assert!(r < self.rows && c < self.cols, "({r}, {c}) out of range");
+        self.data[r * self.stride + c / WORD_BITS] >> (c % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        assert!(r < self.rows && c < self.cols, "({r}, {c}) out of range");
+        let mask = 1 << (c % WORD_BITS);
+        let w = &mut self.data[r * self.stride + c / WORD_BITS];
+        if value {
+            *w |= mask;
+        } else {
+            *w &= !mask;
+        }
+    }
+
+    pub fn toggle(&mut self, r: usize, c: usize) {
+        assert!(r < self.rows && c < self.cols, "({r}, {c}) out of range");
+        self.data[r * self.stride + c / WORD_BITS] ^= 1 << (c % WORD_BITS);
+    }
+
+    pub fn row(&self, r: usize) -> Gf2Vector {
+        Gf2Vector {
+            len: self.cols,
+            words: self.row_words(r).to_vec(),
+        }
+    }
+
+    pub fn column(&self, c: usize) -> Gf2Vector {
+        let mut v = Gf2Vector::zeros(self.rows);
+        for r in 0..self.rows {
+            if self.get(r, c) {
+                v.set(r, true);
+            }
+        }
+        v
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        for w in 0..self.stride {
+            self.data.swap(a * self.stride + w, b * self.stride + w);
+        }
+    }
+
+    /// `row[dst] ^= row[src]`
+    pub fn xor_row(&mut self, dst: usize, src: usize) {
+        if dst == src {
+            self.data[dst * self.stride..(dst + 1) * self.stride].fill(0);
+            return;
+        }
+        for w in 0..self.stride {
+            let s = self.data[src * self.stride + w];
+            self.data[dst * self.stride + w] ^= s;
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in self.row(r).ones() {
+                t.set(c, r, true);
+            }
+        }
+        t
+    }
+
+    pub fn mul_vec(&self, v: &Gf2Vector) -> Gf2Vector {
+        assert_eq!(self.cols, v.len, "dimension mismatch");
+        let mut out = Gf2Vector::zeros(self.rows);
+        for r in 0..self.rows {
+            let parity = self
+                .row_words(r)
+                .iter()
+                .zip(&v.words)
+                .fold(0, |acc, (a, b)| acc ^ (a & b).count_ones());
+            if parity & 1 == 1 {
+                out.set(r, true);
+            }
+        }
+        out
+    }
+
+    pub fn mul(&self, other: &Gf2Matrix) -> Gf2Matrix {
+        assert_eq!(self.cols, other.rows, "dimension mismatch");
+        let mut out = Gf2Matrix::zeros(self.rows, other.cols);
+        for r in 0..self.rows {
+            let dst = r * out.stride;
+            for k in self.row(r).ones() {
+                for (w, &o) in other.row_words(k).iter().enumerate() {
+                    out.data[dst + w] ^= o;
+                }
+            }
+        }
+        out
+    }
+
+    /// `self` multiplied by itself `exp` times, by repeated squaring.
+    pub fn pow(&self, exp: u64) -> Gf2Matrix {
+        assert_eq!(self.rows, self.cols, "only square matrices have powers");
+        let mut result = Gf2Matrix::identity(self.rows);
+        let mut square = self.clone();
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&square);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                square = square.mul(&square);
+            }
+        }
+        result
+    }
+
+    /// Reduces the matrix in place to reduced row echelon form and returns the pivot column of
+    /// each non-zero row, in row order.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        self.row_reduce_cols(self.cols)
+    }
+
+    /// Like [`Gf2Matrix::row_reduce`] but only picks pivots among the first `limit` columns, so
+    /// an augmented right-hand side is carried along without being eliminated on.
+    fn row_reduce_cols(&mut self, limit: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut next_row = 0;
+
+        for c in 0..limit {
+            if next_row == self.rows {
+                break;
+            }
+            let Some(p) = (next_row..self.rows).find(|&r| self.get(r, c)) else {
+                continue;
+            };
+            self.swap_rows(next_row, p);
+            for r in 0..self.rows {
+                if r != next_row && self.get(r, c) {
+                    self.xor_row(r, next_row);
+                }
+            }
+            pivots.push(c);
+            next_row += 1;
+        }
+
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// Basis of `{x : Ax = 0}`, one vector per free column.
+    pub fn null_space(&self) -> Vec<Gf2Vector> {
+        let mut reduced = self.clone();
+        let pivots = reduced.row_reduce();
+        reduced.null_basis(&pivots, self.cols)
+    }
+
+    /// Null-space basis of an already reduced matrix, restricted to its first `cols` columns so
+    /// that an augmented right-hand side column is never treated as a free variable.
+    fn null_basis(&self, pivots: &[usize], cols: usize) -> Vec<Gf2Vector> {
+        let mut is_pivot = vec![false; cols];
+        for &p in pivots {
+            is_pivot[p] = true;
+        }
+
+        (0..cols)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = Gf2Vector::zeros(cols);
+                v.set(f, true);
+                for (r, &p) in pivots.iter().enumerate() {
+                    if self.get(r, f) {
+                        v.set(p, true);
+                    }
+                }
+                v
+            })
+            .collect()
+    }
+
+    /// Solves `Ax = rhs`, returning `None` when the system is inconsistent.
+    pub fn solve(&self, rhs: &Gf2Vector) -> Option<Gf2Solution> {
+        assert_eq!(self.rows, rhs.len, "dimension mismatch");
+
+        let mut aug = Gf2Matrix::zeros(self.rows, self.cols + 1);
+        for r in 0..self.rows {
+            for c in self.row(r).ones() {
+                aug.set(r, c, true);
+            }
+            if rhs.get(r) {
+                aug.set(r, self.cols, true);
+            }
+        }
+
+        let pivots = aug.row_reduce_cols(self.cols);
+        if (pivots.len()..self.rows).any(|r| aug.get(r, self.cols)) {
+            return None;
+        }
+
+        let mut particular = Gf2Vector::zeros(self.cols);
+        for (r, &p) in pivots.iter().enumerate() {
+            if aug.get(r, self.cols) {
+                particular.set(p, true);
+            }
+        }
+
+        let null_basis = aug.null_basis(&pivots, self.cols);
+
+        Some(Gf2Solution {
+            particular,
+            null_basis,
+        })
+    }
+}
+
+/// The powers `m^1, m^2, m^4, ...` of a square matrix, so that `m^n * v` costs one
+/// matrix-vector product per set bit of `n` instead of `n` products.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Powers {
+    squares: Vec<Gf2Matrix>,
+}
+
+impl Gf2Powers {
+    /// Precomputes enough squares of `m` for exponents up to `max_exp`.
+    pub fn new(m: Gf2Matrix, max_exp: u64) -> Self {
+        assert_eq!(m.rows, m.cols, "only square matrices have powers");
+        let count = (u64::BITS - max_exp.leading_zeros()).max(1) as usize;
+        let mut squares = Vec::with_capacity(count);
+        squares.push(m);
+        while squares.len() < count {
+            let last = &squares[squares.len() - 1];
+            squares.push(last.mul(last));
+        }
+        Self { squares }
+    }
+
+    /// Largest exponent [`Gf2Powers::apply`] accepts.
+    pub fn max_exp(&self) -> u64 {
+        u64::MAX >> (u64::BITS as usize - self.squares.len())
+    }
+
+    /// `m^exp * v`.
+    pub fn apply(&self, v: &Gf2Vector, exp: u64) -> Gf2Vector {
+        assert!(
+            exp <= self.max_exp(),
+            "exponent {exp} above the precomputed {}",
+            self.max_exp()
+        );
+        let mut v = v.clone();
+        for (k, square) in self.squares.iter().enumerate() {
+            if exp >> k & 1 == 1 {
+                v = square.mul_vec(&v);
+            }
+        }
+        v
+    }
+}
+
+/// Affine solution space `particular + span(null_basis)` of a consistent system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Solution {
+    pub particular: Gf2Vector,
+    pub null_basis: Vec<Gf2Vector>,
+}
+
+impl Gf2Solution {
+    pub fn free_vars(&self) -> usize {
+        self.null_basis.len()
+    }
+
+    /// Number of solutions, `2^free_vars`, or `None` if that does not fit in a `u64`.
+    pub fn count(&self) -> Option<u64> {
+        u32::try_from(self.free_vars())
+            .ok()
+            .and_then(|free| 1u64.checked_shl(free))
+    }
+
+    fn enumeration_size(&self) -> u64 {
+        self.count().unwrap_or_else(|| {
+            panic!(
+                "{} free variables are too many to enumerate the solutions",
+                self.free_vars()
+            )
+        })
+    }
+
+    /// Every solution, visited in Gray-code order so each step is a single vector XOR.
+    ///
+    /// Yields `2^free_vars` vectors, so only use this when the null space is small. Panics with
+    /// 64 or more free variables.
+    pub fn iter(&self) -> impl Iterator<Item = Gf2Vector> + '_ {
+        let total = self.enumeration_size();
+        let mut current = self.particular.clone();
+        (0..total).map(move |i| {
+            if i > 0 {
+                current.xor_assign(&self.null_basis[i.trailing_zeros() as usize]);
+            }
+            current.clone()
+        })
+    }
+
+    /// Solution with the fewest set bits, found by enumerating the free variables. Panics with
+    /// 64 or more free variables.
+    pub fn min_weight(&self) -> Gf2Vector {
+        let total = self.enumeration_size();
+        let mut current = self.particular.clone();
+        let mut best = current.clone();
+        let mut best_weight = best.weight();
+
+        for i in 1..total {
+            current.xor_assign(&self.null_basis[i.trailing_zeros() as usize]);
+            let w = current.weight();
+            if w < best_weight {
+                best_weight = w;
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rank_and_null_space() {
+        // Columns: {0,1}, {1,2}, {0,2} -> third column is the sum of the first two.
+        let m = Gf2Matrix::from_columns(3, [vec![0, 1], vec![1, 2], vec![0, 2]]);
+        assert_eq!(2, m.rank());
+
+        let null = m.null_space();
+        assert_eq!(1, null.len());
+        assert!(m.mul_vec(&null[0]).is_zero());
+        assert_eq!("111", null[0].to_string());
+    }
+
+    #[test]
+    fn solve_min_weight() {
+        let m = Gf2Matrix::from_columns(
+            4,
+            [
+                vec![3],
+                vec![1, 3],
+                vec![2],
+                vec![2, 3],
+                vec![0, 2],
+                vec![0, 1],
+            ],
+        );
+        let target = Gf2Vector::from_bools(&[false, true, true, false]);
+        let solution = m.solve(&target).expect("consistent system");
+
+        assert_eq!(Some(1 << solution.free_vars()), solution.count());
+        assert_eq!(1 << solution.free_vars(), solution.iter().count());
+        assert!(solution.iter().all(|x| m.mul_vec(&x) == target));
+
+        let best = solution.min_weight();
+        assert_eq!(2, best.weight());
+        assert_eq!(target, m.mul_vec(&best));
+    }
+
+    #[test]
+    fn too_many_solutions_to_count() {
+        let m = Gf2Matrix::zeros(1, 64);
+        let solution = m.solve(&Gf2Vector::zeros(1)).expect("consistent system");
+        assert_eq!(64, solution.free_vars());
+        assert_eq!(None, solution.count());
+    }
+
+    #[test]
+    #[should_panic(expected = "64 free variables are too many")]
+    fn min_weight_refuses_huge_spaces() {
+        let m = Gf2Matrix::zeros(1, 64);
+        m.solve(&Gf2Vector::zeros(1)).unwrap().min_weight();
+    }
+
+    #[test]
+    fn solve_inconsistent() {
+        let m = Gf2Matrix::from_columns(2, [vec![0, 1]]);
+        assert!(m.solve(&Gf2Vector::from_bools(&[true, false])).is_none());
+    }
+
+    #[test]
+    fn powers_of_linear_maps() {
+        // Rotating 5 bits left by one is linear; five rotations are the identity.
+        let rotate = |x: u64| ((x << 1) | (x >> 4)) & 0b11111;
+        let m = Gf2Matrix::from_linear_fn(5, rotate);
+        assert_eq!(Gf2Matrix::identity(5), m.pow(5));
+        assert_eq!(m, m.pow(6));
+        assert_eq!(Gf2Matrix::identity(5), m.pow(0));
+
+        let powers = Gf2Powers::new(m.clone(), 1000);
+        assert_eq!(1023, powers.max_exp());
+        let v = Gf2Vector::from_bits(0b00110, 5);
+        for exp in [0, 1, 2, 7, 999] {
+            let expected = (0..exp % 5).fold(0b00110, |x, _| rotate(x));
+            assert_eq!(expected, powers.apply(&v, exp).to_bits());
+            assert_eq!(m.pow(exp).mul_vec(&v), powers.apply(&v, exp));
+        }
+    }
+
+    #[test]
+    fn wide_rows_span_words() {
+        let mut m = Gf2Matrix::zeros(2, 130);
+        m.set(0, 129, true);
+        m.set(1, 64, true);
+        m.xor_row(0, 1);
+        assert_eq!(vec![64, 129], m.row(0).ones().collect::<Vec<_>>());
+        assert_eq!(m, m.transpose().transpose());
+    }
+}
@@ -0,0 +1,416 @@
+//! Clique search on undirected [`Graph`]s.
+//!
+//! Both searches visit nodes in a [degeneracy ordering](https://en.wikipedia.org/wiki/Degeneracy_(graph_theory)),
+//! which bounds the candidate set at every top-level call by the graph's degeneracy rather than
+//! its maximum degree. Neighbourhoods are kept as bitsets so set intersections are word-wise ANDs.
+
+use std::hash::Hash;
+
+use super::{Graph, NodeId};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Fixed-capacity set of node ids backed by `u64` words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NodeSet {
+    words: Vec<u64>,
+}
+
+impl NodeSet {
+    fn empty(n: usize) -> Self {
+        Self {
+            words: vec![0; n.div_ceil(WORD_BITS)],
+        }
+    }
+
+    fn insert(&mut self, v: NodeId) {
+        self.words[v / WORD_BITS] |= 1 << (v % WORD_BITS);
+    }
+
+    fn remove(&mut self, v: NodeId) {
+        self.words[v / WORD_BITS] &= !(1 << (v % WORD_BITS));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn intersection(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+        }
+    }
+
+    fn intersection_len(&self, other: &NodeSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    fn difference(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & !b)
+                .collect(),
+        }
+    }
+
+    fn union(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a | b)
+                .collect(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.words.iter().enumerate().flat_map(|(wi, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit = w.trailing_zeros() as usize;
+                w &= w - 1;
+                Some(wi * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+/// Shared state for the Bron–Kerbosch recursion.
+struct CliqueSearch {
+    neighbors: Vec<NodeSet>,
+    clique: Vec<NodeId>,
+    /// Branches that cannot produce a clique larger than this are skipped.
+    bound: usize,
+}
+
+impl CliqueSearch {
+    /// Bron–Kerbosch with Tomita pivoting. `visit` sees each maximal clique extending the
+    /// current one and returns the size a later clique must beat to be worth exploring, which
+    /// lets the maximum-clique search prune branches that cannot win.
+    fn extend(
+        &mut self,
+        mut p: NodeSet,
+        mut x: NodeSet,
+        visit: &mut impl FnMut(&[NodeId]) -> usize,
+    ) {
+        if p.is_empty() {
+            if x.is_empty() {
+                self.bound = visit(&self.clique);
+            }
+            return;
+        }
+
+        let pivot = p
+            .union(&x)
+            .iter()
+            .max_by_key(|&u| p.intersection_len(&self.neighbors[u]))
+            .expect("P is non-empty");
+
+        let candidates = p
+            .difference(&self.neighbors[pivot])
+            .iter()
+            .collect::<Vec<_>>();
+        for v in candidates {
+            if self.clique.len() + p.len() <= self.bound {
+                return;
+            }
+
+            let next_p = p.intersection(&self.neighbors[v]);
+            let next_x = x.intersection(&self.neighbors[v]);
+            self.clique.push(v);
+            self.extend(next_p, next_x, visit);
+            self.clique.pop();
+
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> Graph<K> {
+    fn neighbor_sets(&self) -> Vec<NodeSet> {
+        assert!(
+            !self.directed,
+            "cliques are only defined on undirected graphs"
+        );
+
+        let n = self.node_count();
+        self.adj
+            .iter()
+            .enumerate()
+            .map(|(u, edges)| {
+                let mut set = NodeSet::empty(n);
+                for &v in edges.iter().filter(|&&v| v != u) {
+                    set.insert(v);
+                }
+                set
+            })
+            .collect()
+    }
+
+    /// Nodes ordered by repeatedly removing one of minimum remaining degree.
+    ///
+    /// Returns the order together with the graph's degeneracy, the largest degree seen at
+    /// removal time.
+    pub fn degeneracy_ordering(&self) -> (Vec<NodeId>, usize) {
+        let n = self.node_count();
+        let mut degree = self.adj.iter().map(Vec::len).collect::<Vec<_>>();
+        let max_degree = degree.iter().copied().max().unwrap_or(0);
+        let mut buckets = vec![Vec::new(); max_degree + 1];
+        for u in (0..n).rev() {
+            buckets[degree[u]].push(u);
+        }
+
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut degeneracy = 0;
+        let mut d = 0;
+        while order.len() < n {
+            // Buckets hold stale entries for nodes whose degree dropped since they were pushed;
+            // skip those instead of deleting them eagerly.
+            let Some(u) = buckets[d].pop() else {
+                d += 1;
+                continue;
+            };
+            if removed[u] || degree[u] != d {
+                continue;
+            }
+
+            removed[u] = true;
+            degeneracy = degeneracy.max(d);
+            order.push(u);
+            for &v in &self.adj[u] {
+                if !removed[v] {
+                    degree[v] -= 1;
+                    buckets[degree[v]].push(v);
+                }
+            }
+            d = d.saturating_sub(1);
+        }
+
+        (order, degeneracy)
+    }
+
+    /// Runs Bron–Kerbosch from each node in degeneracy order, restricting candidates to nodes
+    /// later in the order so every maximal clique is reported exactly once.
+    fn bron_kerbosch(&self, visit: &mut impl FnMut(&[NodeId]) -> usize) {
+        let n = self.node_count();
+        let neighbors = self.neighbor_sets();
+        let (order, _) = self.degeneracy_ordering();
+
+        let mut later = NodeSet::empty(n);
+        for &v in &order {
+            later.insert(v);
+        }
+
+        let mut search = CliqueSearch {
+            neighbors,
+            clique: Vec::new(),
+            bound: 0,
+        };
+        let mut earlier = NodeSet::empty(n);
+        for &v in &order {
+            later.remove(v);
+            let p = later.intersection(&search.neighbors[v]);
+            let x = earlier.intersection(&search.neighbors[v]);
+
+            if 1 + p.len() > search.bound {
+                search.clique.push(v);
+                search.extend(p, x, visit);
+                search.clique.pop();
+            }
+
+            earlier.insert(v);
+        }
+    }
+
+    /// Every maximal clique, each sorted by node id.
+    pub fn maximal_cliques(&self) -> Vec<Vec<NodeId>> {
+        let mut cliques = Vec::new();
+        self.bron_kerbosch(&mut |clique| {
+            let mut clique = clique.to_vec();
+            clique.sort_unstable();
+            cliques.push(clique);
+            0
+        });
+        cliques
+    }
+
+    /// A maximum clique sorted by node id.
+    ///
+    /// The search is exact: branches are only cut once they cannot beat the best clique found
+    /// so far. Among equally large cliques the first one reached in degeneracy order wins, so
+    /// the result is deterministic for a given graph.
+    pub fn max_clique(&self) -> Vec<NodeId> {
+        let mut best: Vec<NodeId> = Vec::new();
+        self.bron_kerbosch(&mut |clique| {
+            if clique.len() > best.len() {
+                best = clique.to_vec();
+            }
+            best.len()
+        });
+        best.sort_unstable();
+        best
+    }
+
+    /// Calls `visit` once for every clique of exactly `k` nodes, each sorted by degeneracy order.
+    ///
+    /// Edges are oriented from earlier to later nodes in the degeneracy ordering, so each clique
+    /// is built only from its first node and candidate sets stay small.
+    pub fn for_each_k_clique(&self, k: usize, mut visit: impl FnMut(&[NodeId])) {
+        if k == 0 {
+            return;
+        }
+
+        let n = self.node_count();
+        let neighbors = self.neighbor_sets();
+        let (order, _) = self.degeneracy_ordering();
+        let mut rank = vec![0; n];
+        for (i, &v) in order.iter().enumerate() {
+            rank[v] = i;
+        }
+
+        let forward = (0..n)
+            .map(|u| {
+                let mut set = NodeSet::empty(n);
+                for v in neighbors[u].iter().filter(|&v| rank[v] > rank[u]) {
+                    set.insert(v);
+                }
+                set
+            })
+            .collect::<Vec<_>>();
+
+        fn grow(
+            forward: &[NodeSet],
+            candidates: &NodeSet,
+            clique: &mut Vec<NodeId>,
+            k: usize,
+            visit: &mut impl FnMut(&[NodeId]),
+        ) {
+            if clique.len() == k {
+                visit(clique);
+                return;
+            }
+            if clique.len() + candidates.len() < k {
+                return;
+            }
+            for v in candidates.iter() {
+                clique.push(v);
+                grow(
+                    forward,
+                    &candidates.intersection(&forward[v]),
+                    clique,
+                    k,
+                    visit,
+                );
+                clique.pop();
+            }
+        }
+
+        let mut clique = Vec::with_capacity(k);
+        for &v in &order {
+            clique.push(v);
+            grow(&forward, &forward[v], &mut clique, k, &mut visit);
+            clique.pop();
+        }
+    }
+
+    /// Every clique of exactly `k` nodes, each sorted by node id.
+    pub fn k_cliques(&self, k: usize) -> Vec<Vec<NodeId>> {
+        let mut cliques = Vec::new();
+        self.for_each_k_clique(k, |clique| {
+            let mut clique = clique.to_vec();
+            clique.sort_unstable();
+            cliques.push(clique);
+        });
+        cliques
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two triangles sharing the edge b-c, plus a K4 on w, x, y, z hanging off d.
+    fn sample() -> Graph<&'static str> {
+        let mut g = Graph::undirected();
+        for (a, b) in [
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "w"),
+            ("w", "x"),
+            ("w", "y"),
+            ("w", "z"),
+            ("x", "y"),
+            ("x", "z"),
+            ("y", "z"),
+        ] {
+            g.add_edge(a, b);
+        }
+        g
+    }
+
+    fn names(g: &Graph<&'static str>, ids: &[NodeId]) -> Vec<&'static str> {
+        let mut names = g.names(ids).copied().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn maximum_and_maximal() {
+        let g = sample();
+        assert_eq!(vec!["w", "x", "y", "z"], names(&g, &g.max_clique()));
+
+        let mut maximal = g
+            .maximal_cliques()
+            .iter()
+            .map(|c| names(&g, c))
+            .collect::<Vec<_>>();
+        maximal.sort();
+        assert_eq!(
+            vec![
+                vec!["a", "b", "c"],
+                vec!["b", "c", "d"],
+                vec!["d", "w"],
+                vec!["w", "x", "y", "z"],
+            ],
+            maximal
+        );
+    }
+
+    #[test]
+    fn k_cliques() {
+        let g = sample();
+        // Two triangles on the left and C(4, 3) inside the K4.
+        assert_eq!(6, g.k_cliques(3).len());
+        assert_eq!(1, g.k_cliques(4).len());
+        assert!(g.k_cliques(5).is_empty());
+        assert_eq!(g.edge_count(), g.k_cliques(2).len());
+
+        let (_, degeneracy) = g.degeneracy_ordering();
+        assert_eq!(3, degeneracy);
+    }
+}
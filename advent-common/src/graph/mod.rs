@@ -0,0 +1,578 @@
+//! Shared graph helpers keyed by puzzle node names.
+//!
+//! Nodes are interned into dense [`NodeId`]s so the algorithms can work on plain `Vec`s instead
+//! of hashing names on every step. Names are only needed at the edges of a solution: building
+//! the graph and reporting the answer.
+
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::{self, Display, Write};
+use std::hash::Hash;
+
+use crate::AdventError;
+use crate::fasthash::FastSet;
+pub use crate::intern::Interner;
+
+mod clique;
+
+pub type NodeId = usize;
+
+/// Nodes that form a directed cycle, in edge order (the last node links back to the first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub nodes: Vec<NodeId>,
+}
+
+impl From<Cycle> for AdventError {
+    fn from(cycle: Cycle) -> Self {
+        AdventError::InputParseError(format!("graph has a cycle through {:?}", cycle.nodes).into())
+    }
+}
+
+/// Why [`Graph::count_paths_through`] could not produce a count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathCountError {
+    /// The reachable part of the graph has a cycle, so the count would be unbounded.
+    Cycle(Cycle),
+    /// More distinct waypoints than the bitmask DP supports.
+    TooManyWaypoints(usize),
+    /// The count does not fit in a `u128`.
+    Overflow,
+}
+
+impl From<Cycle> for PathCountError {
+    fn from(cycle: Cycle) -> Self {
+        Self::Cycle(cycle)
+    }
+}
+
+impl From<PathCountError> for AdventError {
+    fn from(err: PathCountError) -> Self {
+        match err {
+            PathCountError::Cycle(cycle) => cycle.into(),
+            PathCountError::TooManyWaypoints(n) => AdventError::InputParseError(
+                format!("{n} waypoints, at most {MAX_WAYPOINTS} are supported").into(),
+            ),
+            PathCountError::Overflow => {
+                AdventError::InputParseError("path count overflows u128".into())
+            }
+        }
+    }
+}
+
+/// Largest number of distinct waypoints [`Graph::count_paths_through`] accepts.
+pub const MAX_WAYPOINTS: usize = 20;
+
+/// Adjacency-list graph over interned node names.
+///
+/// Undirected graphs store each edge in both directions. Parallel edges are collapsed, so every
+/// algorithm below sees a simple graph.
+#[derive(Debug, Clone)]
+pub struct Graph<K> {
+    nodes: Interner<K>,
+    adj: Vec<Vec<NodeId>>,
+    /// Every `(u, v)` in `adj`, so adding or testing an edge does not scan a neighbour list.
+    edges: FastSet<(NodeId, NodeId)>,
+    directed: bool,
+}
+
+impl<K: Eq + Hash + Clone> Graph<K> {
+    pub fn directed() -> Self {
+        Self {
+            nodes: Interner::new(),
+            adj: Vec::new(),
+            edges: FastSet::default(),
+            directed: true,
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn add_node(&mut self, name: K) -> NodeId {
+        let id = self.nodes.intern(name);
+        if id == self.adj.len() {
+            self.adj.push(Vec::new());
+        }
+        id
+    }
+
+    /// Like [`Graph::add_node`], but only converts `name` to an owned key the first time it is
+    /// seen, e.g. a `&str` for a `Graph<String>`.
+    pub fn add_node_ref<Q>(&mut self, name: &Q) -> NodeId
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        let id = self.nodes.intern_ref(name);
+        if id == self.adj.len() {
+            self.adj.push(Vec::new());
+        }
+        id
+    }
+
+    /// Adds `from -> to` (and `to -> from` for undirected graphs), interning both names.
+    pub fn add_edge(&mut self, from: K, to: K) -> (NodeId, NodeId) {
+        let u = self.add_node(from);
+        let v = self.add_node(to);
+        self.add_edge_ids(u, v);
+        (u, v)
+    }
+
+    /// [`Graph::add_edge`] with names interned by [`Graph::add_node_ref`].
+    pub fn add_edge_ref<Q>(&mut self, from: &Q, to: &Q) -> (NodeId, NodeId)
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        let u = self.add_node_ref(from);
+        let v = self.add_node_ref(to);
+        self.add_edge_ids(u, v);
+        (u, v)
+    }
+
+    pub fn add_edge_ids(&mut self, u: NodeId, v: NodeId) {
+        if self.edges.insert((u, v)) {
+            self.adj[u].push(v);
+        }
+        if !self.directed && self.edges.insert((v, u)) {
+            self.adj[v].push(u);
+        }
+    }
+
+    pub fn id<Q>(&self, name: &Q) -> Option<NodeId>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.nodes.get(name)
+    }
+
+    /// Like [`Graph::id`] but reports a missing node as an input error.
+    pub fn require<Q>(&self, name: &Q) -> Result<NodeId, AdventError>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + Display + ?Sized,
+    {
+        self.id(name)
+            .ok_or_else(|| AdventError::InputParseError(format!("unknown node {name}").into()))
+    }
+
+    pub fn name(&self, id: NodeId) -> &K {
+        self.nodes.name(id)
+    }
+
+    pub fn names<'a>(&'a self, ids: &'a [NodeId]) -> impl Iterator<Item = &'a K> + 'a {
+        ids.iter().map(|&id| self.name(id))
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        let total: usize = self.adj.iter().map(Vec::len).sum();
+        if self.directed { total } else { total / 2 }
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> &[NodeId] {
+        &self.adj[id]
+    }
+
+    pub fn has_edge(&self, u: NodeId, v: NodeId) -> bool {
+        self.edges.contains(&(u, v))
+    }
+
+    /// Nodes in dependency order (every edge points forward), via Kahn's algorithm.
+    ///
+    /// Whenever several nodes are ready, the smallest id (the earliest inserted) comes next, so
+    /// the result is deterministic.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, Cycle> {
+        let n = self.node_count();
+        let mut in_degree = vec![0usize; n];
+        for edges in &self.adj {
+            for &v in edges {
+                in_degree[v] += 1;
+            }
+        }
+
+        let mut ready: BinaryHeap<Reverse<NodeId>> =
+            (0..n).filter(|&u| in_degree[u] == 0).map(Reverse).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(Reverse(u)) = ready.pop() {
+            order.push(u);
+            for &v in &self.adj[u] {
+                in_degree[v] -= 1;
+                if in_degree[v] == 0 {
+                    ready.push(Reverse(v));
+                }
+            }
+        }
+
+        if order.len() == n {
+            return Ok(order);
+        }
+
+        // Every node Kahn's algorithm could not emit sits on or behind a cycle, so a DFS over
+        // just those nodes must hit a back edge.
+        let remaining = in_degree.iter().map(|&d| d > 0).collect::<Vec<_>>();
+        Err(self
+            .post_order(0..n, &remaining)
+            .expect_err("nodes left over by Kahn's algorithm must contain a cycle"))
+    }
+
+    /// Strongly connected components via Tarjan's algorithm.
+    ///
+    /// Components come out in reverse topological order of the condensation: no component has an
+    /// edge into a component listed after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let n = self.node_count();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            let mut call_stack = vec![(root, 0usize)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (u, ref mut next)) = call_stack.last_mut() {
+                if let Some(&v) = self.adj[u].get(*next) {
+                    *next += 1;
+                    if index[v] == usize::MAX {
+                        index[v] = next_index;
+                        low[v] = next_index;
+                        next_index += 1;
+                        stack.push(v);
+                        on_stack[v] = true;
+                        call_stack.push((v, 0));
+                    } else if on_stack[v] {
+                        low[u] = low[u].min(index[v]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    low[parent] = low[parent].min(low[u]);
+                }
+
+                if low[u] == index[u] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == u {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Marks every node reachable from `from` (including `from` itself).
+    pub fn reachable(&self, from: NodeId) -> Vec<bool> {
+        let mut seen = vec![false; self.node_count()];
+        seen[from] = true;
+        let mut queue = VecDeque::from([from]);
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adj[u] {
+                if !seen[v] {
+                    seen[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        seen
+    }
+
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        self.reachable(from)[to]
+    }
+
+    /// Number of distinct `from -> to` paths.
+    ///
+    /// Each node's count is memoised after its successors are finished, so this is linear in the
+    /// part of the graph reachable from `from`. Fails if that part contains a cycle, since the
+    /// count would be unbounded, or if the count overflows `u128`.
+    pub fn count_paths(&self, from: NodeId, to: NodeId) -> Result<u128, PathCountError> {
+        self.count_paths_through(from, to, &[])
+    }
+
+    /// Number of distinct `from -> to` paths that visit every node in `via`, in any order.
+    ///
+    /// Runs a bitmask DP over the DAG: for each node it keeps one count per subset of `via`
+    /// already seen on the way to `to`, so the cost is `O((V + E) * 2^via.len())`. Fails if the
+    /// part of the graph reachable from `from` contains a cycle, if `via` has more than
+    /// [`MAX_WAYPOINTS`] distinct nodes, or if a count overflows `u128`.
+    pub fn count_paths_through(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        via: &[NodeId],
+    ) -> Result<u128, PathCountError> {
+        let mut waypoints = via.to_vec();
+        waypoints.sort_unstable();
+        waypoints.dedup();
+        if waypoints.len() > MAX_WAYPOINTS {
+            return Err(PathCountError::TooManyWaypoints(waypoints.len()));
+        }
+
+        let mut bit = vec![0usize; self.node_count()];
+        for (i, &w) in waypoints.iter().enumerate() {
+            bit[w] = 1 << i;
+        }
+
+        let masks = 1usize << waypoints.len();
+        let order = self.post_order([from], &vec![true; self.node_count()])?;
+
+        // `counts[u * masks + m]` is the number of `u -> to` paths whose waypoints are exactly `m`.
+        let mut counts = vec![0u128; self.node_count() * masks];
+        for u in order {
+            let row = u * masks;
+            if u == to {
+                counts[row + bit[u]] = 1;
+                continue;
+            }
+            for &v in &self.adj[u] {
+                for m in 0..masks {
+                    let c = counts[v * masks + m];
+                    if c == 0 {
+                        continue;
+                    }
+                    let slot = &mut counts[row + (m | bit[u])];
+                    *slot = slot.checked_add(c).ok_or(PathCountError::Overflow)?;
+                }
+            }
+        }
+
+        Ok(counts[from * masks + masks - 1])
+    }
+
+    /// Nodes reachable from `roots` in DFS post-order, so every successor precedes its
+    /// predecessors. Only nodes marked in `within` are visited. Fails on the first back edge.
+    fn post_order(
+        &self,
+        roots: impl IntoIterator<Item = NodeId>,
+        within: &[bool],
+    ) -> Result<Vec<NodeId>, Cycle> {
+        const UNSEEN: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNSEEN; self.node_count()];
+        let mut order = Vec::new();
+        for root in roots {
+            if !within[root] || state[root] != UNSEEN {
+                continue;
+            }
+
+            let mut stack = vec![(root, 0usize)];
+            state[root] = ON_STACK;
+            while let Some(&mut (u, ref mut next)) = stack.last_mut() {
+                let Some(&v) = self.adj[u].get(*next) else {
+                    state[u] = DONE;
+                    order.push(u);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+
+                if !within[v] {
+                    continue;
+                }
+                match state[v] {
+                    UNSEEN => {
+                        state[v] = ON_STACK;
+                        stack.push((v, 0));
+                    }
+                    ON_STACK => {
+                        let start = stack.iter().position(|&(w, _)| w == v).unwrap();
+                        return Err(Cycle {
+                            nodes: stack[start..].iter().map(|&(w, _)| w).collect(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(order)
+    }
+}
+
+impl<K: Eq + Hash + Clone + Display> Graph<K> {
+    /// Renders the graph in Graphviz DOT format, e.g. to eyeball puzzle input structure.
+    pub fn to_dot(&self) -> String {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut out = String::new();
+        writeln!(out, "{kind} {{").unwrap();
+        for u in 0..self.node_count() {
+            writeln!(out, "    {u} [label=\"{}\"];", self.name(u)).unwrap();
+        }
+        for (u, edges) in self.adj.iter().enumerate() {
+            for &v in edges {
+                if self.directed || u <= v {
+                    writeln!(out, "    {u} {arrow} {v};").unwrap();
+                }
+            }
+        }
+        out.push('}');
+        out
+    }
+
+    /// Human-readable `a -> b -> a` rendering of a [`Cycle`] found in this graph.
+    pub fn describe_cycle(&self, cycle: &Cycle) -> String {
+        let mut names = self
+            .names(&cycle.nodes)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if let Some(first) = names.first().cloned() {
+            names.push(first);
+        }
+        names.join(" -> ")
+    }
+}
+
+impl<K: Eq + Hash + Clone + Display> Display for Graph<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_dot())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diamond() -> Graph<&'static str> {
+        let mut g = Graph::directed();
+        g.add_edge("a", "b");
+        g.add_edge("a", "c");
+        g.add_edge("b", "d");
+        g.add_edge("c", "d");
+        g.add_edge("d", "e");
+        g
+    }
+
+    #[test]
+    fn toposort_and_paths() {
+        let g = diamond();
+        let order = g.toposort().unwrap();
+        let names = g.names(&order).copied().collect::<Vec<_>>();
+        assert_eq!(vec!["a", "b", "c", "d", "e"], names);
+
+        // Breadth-first order would emit "y" before "b"; smallest id first takes "b" as soon as
+        // it is ready.
+        let mut late = Graph::directed();
+        late.add_edge("a", "x");
+        late.add_edge("x", "b");
+        late.add_node("y");
+        late.add_edge("a", "y");
+        let order = late.toposort().unwrap();
+        assert_eq!(
+            vec!["a", "x", "b", "y"],
+            late.names(&order).copied().collect::<Vec<_>>()
+        );
+
+        let (a, e) = (g.id("a").unwrap(), g.id("e").unwrap());
+        assert_eq!(Ok(2), g.count_paths(a, e));
+        assert_eq!(Ok(0), g.count_paths(e, a));
+        assert!(g.is_reachable(a, e));
+        assert!(!g.is_reachable(e, a));
+
+        let (b, c, d) = (g.id("b").unwrap(), g.id("c").unwrap(), g.id("d").unwrap());
+        assert_eq!(Ok(1), g.count_paths_through(a, e, &[b]));
+        assert_eq!(Ok(2), g.count_paths_through(a, e, &[d, d]));
+        assert_eq!(Ok(0), g.count_paths_through(a, e, &[b, c]));
+    }
+
+    #[test]
+    fn path_count_errors() {
+        // 130 diamonds in a row: 2^130 paths from end to end.
+        let mut g = Graph::directed();
+        for i in 0..130u32 {
+            let (top, bottom, next) = (3 * i, 3 * i + 1, 3 * i + 3);
+            g.add_edge(3 * i + 2, top);
+            g.add_edge(3 * i + 2, bottom);
+            g.add_edge(top, next + 2);
+            g.add_edge(bottom, next + 2);
+        }
+        let (from, to) = (g.id(&2).unwrap(), g.id(&(3 * 130 + 2)).unwrap());
+        assert_eq!(Err(PathCountError::Overflow), g.count_paths(from, to));
+
+        let via = (0..=MAX_WAYPOINTS as u32)
+            .map(|n| g.id(&n).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Err(PathCountError::TooManyWaypoints(MAX_WAYPOINTS + 1)),
+            g.count_paths_through(from, to, &via)
+        );
+    }
+
+    #[test]
+    fn cycle_reporting() {
+        let mut g = diamond();
+        g.add_edge("e", "b");
+
+        let cycle = g.toposort().unwrap_err();
+        assert_eq!("b -> d -> e -> b", g.describe_cycle(&cycle));
+
+        let a = g.id("a").unwrap();
+        assert!(matches!(
+            g.count_paths(a, g.id("e").unwrap()),
+            Err(PathCountError::Cycle(_))
+        ));
+
+        let mut sccs = g
+            .strongly_connected_components()
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect::<Vec<_>>();
+        sccs.sort();
+        assert_eq!(vec![vec![0], vec![1, 3, 4], vec![2]], sccs);
+    }
+
+    #[test]
+    fn undirected_dot() {
+        let mut g = Graph::undirected();
+        g.add_edge("x", "y");
+        g.add_edge("y", "x");
+        assert_eq!(1, g.edge_count());
+        assert_eq!(
+            "graph {\n    0 [label=\"x\"];\n    1 [label=\"y\"];\n    0 -- 1;\n}",
+            g.to_dot()
+        );
+    }
+}
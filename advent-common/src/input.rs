@@ -0,0 +1,233 @@
+//! Helpers for the common shapes of puzzle input.
+//!
+//! - [`sections`] and [`split_sections`] split the input on blank lines.
+//! - [`signed_ints`] and [`signed_ints_n`] pull every integer out of a line, ignoring the text
+//!   around them.
+//! - [`header`] and [`header_value`] read `key: value` lines.
+//!
+//! Line indexes are 0-based positions in the full input. Errors raised while parsing a line are
+//! tagged with that position through [`AdventError::at_line`], and show up 1-based like an editor.
+
+use std::any::type_name;
+use std::str::FromStr;
+
+use crate::AdventError;
+
+/// A run of consecutive non-blank lines.
+#[derive(Debug)]
+pub struct Section<'a, S> {
+    /// Index of the section's first line in the full input.
+    pub start: usize,
+    pub lines: &'a [S],
+}
+
+impl<S> Clone for Section<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Section<'_, S> {}
+
+impl<'a, S: AsRef<str>> Section<'a, S> {
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Each line together with its index in the full input.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &'a str)> + use<'a, S> {
+        let start = self.start;
+        self.lines
+            .iter()
+            .enumerate()
+            .map(move |(i, line)| (start + i, line.as_ref()))
+    }
+
+    /// Parses every line with `parse`, tagging the first error with the line it came from.
+    pub fn parse_lines<T>(
+        &self,
+        mut parse: impl FnMut(&'a str) -> Result<T, AdventError>,
+    ) -> Result<Vec<T>, AdventError> {
+        self.iter()
+            .map(|(i, line)| parse(line).map_err(|e| e.at_line(i)))
+            .collect()
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Splits `lines` into maximal runs of non-blank lines. Leading, trailing and repeated blank
+/// lines produce no empty sections.
+pub fn sections<S: AsRef<str>>(lines: &[S]) -> Vec<Section<'_, S>> {
+    let mut sections = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if is_blank(lines[i].as_ref()) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && !is_blank(lines[i].as_ref()) {
+            i += 1;
+        }
+        sections.push(Section {
+            start,
+            lines: &lines[start..i],
+        });
+    }
+    sections
+}
+
+/// Like [`sections`], for inputs with a fixed number of sections.
+pub fn split_sections<S: AsRef<str>, const N: usize>(
+    lines: &[S],
+) -> Result<[Section<'_, S>; N], AdventError> {
+    let found = sections(lines);
+    let count = found.len();
+    found.try_into().map_err(|_| {
+        AdventError::InputParseError(
+            format!("expected {N} blank-line separated sections, found {count}").into(),
+        )
+    })
+}
+
+/// Every integer in `line`, in order.
+///
+/// A `-` directly before a digit is read as a sign unless it follows another digit, so ranges
+/// such as `3-5` give `[3, 5]`. Fails with the column of the first integer that does not fit
+/// in `T`, e.g. a negative number parsed as unsigned.
+pub fn signed_ints<T: FromStr>(line: &str) -> Result<Vec<T>, AdventError> {
+    let bytes = line.as_bytes();
+    let mut ints = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && !(i > 0 && bytes[i - 1].is_ascii_digit());
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let token = &line[start..i];
+        let value = token.parse().map_err(|_| {
+            AdventError::InputParseError(
+                format!(
+                    "column {}: {token:?} is not a valid {}",
+                    start + 1,
+                    type_name::<T>()
+                )
+                .into(),
+            )
+        })?;
+        ints.push(value);
+    }
+    Ok(ints)
+}
+
+/// Exactly `N` integers from `line`, see [`signed_ints`].
+pub fn signed_ints_n<T: FromStr, const N: usize>(line: &str) -> Result<[T; N], AdventError> {
+    let ints = signed_ints(line)?;
+    let count = ints.len();
+    ints.try_into().map_err(|_| {
+        AdventError::InputParseError(
+            format!("expected {N} integers in {line:?}, found {count}").into(),
+        )
+    })
+}
+
+/// The trimmed value of a `key: value` line, where `index` is the line's position in the input.
+pub fn header<'a>(index: usize, line: &'a str, key: &str) -> Result<&'a str, AdventError> {
+    match line.split_once(':') {
+        Some((k, value)) if k.trim() == key => Ok(value.trim()),
+        _ => Err(AdventError::InputParseError(
+            format!("expected `{key}: ...`, found {line:?}").into(),
+        )
+        .at_line(index)),
+    }
+}
+
+/// The value of a `key: value` line, parsed as `T`.
+pub fn header_value<T: FromStr>(index: usize, line: &str, key: &str) -> Result<T, AdventError> {
+    let value = header(index, line, key)?;
+    value.parse().map_err(|_| {
+        AdventError::InputParseError(
+            format!("`{key}`: {value:?} is not a valid {}", type_name::<T>()).into(),
+        )
+        .at_line(index)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn blank_line_sections() {
+        let lines = ["", "a", "b", "", "", "c", "  ", "d", ""];
+        let found = sections(&lines);
+        assert_eq!(3, found.len());
+        assert_eq!(
+            vec![(1, "a"), (2, "b")],
+            found[0].iter().collect::<Vec<_>>()
+        );
+        assert_eq!(5, found[1].start);
+        assert_eq!(["d"], found[2].lines);
+
+        assert!(split_sections::<_, 3>(&lines).is_ok());
+        let err = split_sections::<_, 2>(&lines).unwrap_err();
+        assert_eq!(
+            "expected 2 blank-line separated sections, found 3",
+            err.to_string()
+        );
+
+        let (i, line) = found[1].iter().next().unwrap();
+        let err = header_value::<u32>(i, line, "n").unwrap_err();
+        assert_eq!("line 6: expected `n: ...`, found \"c\"", err.to_string());
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(
+            vec![94, 34],
+            signed_ints::<u64>("Button A: X+94, Y+34").unwrap()
+        );
+        assert_eq!(
+            vec![-3, 5, 10, -2],
+            signed_ints::<i32>("p=-3,5 v=10,-2").unwrap()
+        );
+        assert_eq!([3, 5], signed_ints_n::<u32, 2>("3-5").unwrap());
+        assert_eq!(
+            "column 3: \"-3\" is not a valid u32",
+            signed_ints::<u32>("p=-3").unwrap_err().to_string()
+        );
+        assert!(signed_ints_n::<u32, 3>("1,2").is_err());
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(
+            729,
+            header_value::<u64>(0, "Register A: 729", "Register A").unwrap()
+        );
+        assert_eq!("0,1,5", header(4, "Program: 0,1,5", "Program").unwrap());
+        assert!(header(1, "Register B: 0", "Register A").is_err());
+        assert_eq!(
+            "line 3: `Register A`: \"x\" is not a valid u64",
+            header_value::<u64>(2, "Register A: x", "Register A")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
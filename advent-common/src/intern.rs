@@ -0,0 +1,111 @@
+//! Interning: mapping names to dense ids so hot code can index `Vec`s instead of hashing.
+//!
+//! [`Interner`] works for any hashable key. For text, [`StringInterner`] owns each distinct name
+//! once and is looked up with plain `&str`s, so re-interning a name it has already seen does
+//! not allocate.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use crate::fasthash::FastMap;
+
+/// Bidirectional mapping between names and dense ids assigned in insertion order.
+#[derive(Debug, Clone)]
+pub struct Interner<K> {
+    ids: FastMap<K, usize>,
+    names: Vec<K>,
+}
+
+/// Interner for owned names that accepts borrowed `&str`s via [`Interner::intern_ref`].
+pub type StringInterner = Interner<String>;
+
+impl<K> Default for Interner<K> {
+    fn default() -> Self {
+        Self {
+            ids: FastMap::default(),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> Interner<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, assigning the next free id if it has not been seen before.
+    pub fn intern(&mut self, name: K) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        self.insert(name)
+    }
+
+    /// Like [`Interner::intern`], but only converts `name` to an owned key the first time it is
+    /// seen.
+    pub fn intern_ref<Q>(&mut self, name: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        match self.ids.get(name) {
+            Some(&id) => id,
+            None => self.insert(name.to_owned()),
+        }
+    }
+
+    fn insert(&mut self, name: K) -> usize {
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    pub fn get<Q>(&self, name: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &K {
+        &self.names[id]
+    }
+
+    /// Every name, in id order.
+    pub fn names(&self) -> &[K] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dense_ids() {
+        let mut names = StringInterner::new();
+        assert_eq!(0, names.intern_ref("you"));
+        assert_eq!(1, names.intern("out".to_string()));
+        assert_eq!(0, names.intern_ref("you"));
+        assert_eq!(Some(1), names.get("out"));
+        assert_eq!(None, names.get("svr"));
+        assert_eq!("out", names.name(1));
+        assert_eq!(["you", "out"], names.names());
+        assert_eq!(2, names.len());
+
+        let mut pairs = Interner::new();
+        assert_eq!(0, pairs.intern((3, 4)));
+        assert_eq!(1, pairs.intern((4, 3)));
+        assert_eq!(0, pairs.intern_ref(&(3, 4)));
+    }
+}
@@ -0,0 +1,176 @@
+//! A static [k-d tree](https://en.wikipedia.org/wiki/K-d_tree) over 3D points.
+//!
+//! The tree is built once by splitting on the median along x, y and z in turn, and stored as a
+//! permutation of point indices: the median of each slice is its node, with the two halves as
+//! subtrees. Queries use squared Euclidean distance and report the indices the points had in
+//! the slice passed to [`KdTree::new`].
+
+use std::collections::BinaryHeap;
+
+use crate::point3::Point3;
+
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<Point3>,
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    pub fn new(points: &[Point3]) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        build(points, &mut order, 0);
+        Self {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, i: usize) -> Point3 {
+        self.points[i]
+    }
+
+    /// The `k` points closest to `query` as `(dist_sq, index)`, nearest first. Ties are broken
+    /// by index.
+    pub fn nearest(&self, query: &Point3, k: usize) -> Vec<(u64, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.nearest_in(&self.order, 0, query, k, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn nearest_in(
+        &self,
+        slice: &[usize],
+        axis: usize,
+        query: &Point3,
+        k: usize,
+        best: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if slice.is_empty() {
+            return;
+        }
+        let mid = slice.len() / 2;
+        let node = slice[mid];
+        let candidate = (self.points[node].dist_sq(query), node);
+        if best.len() < k {
+            best.push(candidate);
+        } else if best.peek().is_some_and(|&worst| candidate < worst) {
+            best.pop();
+            best.push(candidate);
+        }
+
+        let diff = query.axis(axis) - self.points[node].axis(axis);
+        let (near, far) = if diff < 0 {
+            (&slice[..mid], &slice[mid + 1..])
+        } else {
+            (&slice[mid + 1..], &slice[..mid])
+        };
+        let next = (axis + 1) % 3;
+        self.nearest_in(near, next, query, k, best);
+        let plane = diff.unsigned_abs().pow(2);
+        if best.len() < k || best.peek().is_some_and(|&(worst, _)| plane <= worst) {
+            self.nearest_in(far, next, query, k, best);
+        }
+    }
+
+    /// Indices of every point within `radius_sq` (inclusive) of `query`, in no particular
+    /// order.
+    pub fn within(&self, query: &Point3, radius_sq: u64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.within_in(&self.order, 0, query, radius_sq, &mut found);
+        found
+    }
+
+    fn within_in(
+        &self,
+        slice: &[usize],
+        axis: usize,
+        query: &Point3,
+        radius_sq: u64,
+        found: &mut Vec<usize>,
+    ) {
+        if slice.is_empty() {
+            return;
+        }
+        let mid = slice.len() / 2;
+        let node = slice[mid];
+        if self.points[node].dist_sq(query) <= radius_sq {
+            found.push(node);
+        }
+
+        let diff = query.axis(axis) - self.points[node].axis(axis);
+        let next = (axis + 1) % 3;
+        let plane = diff.unsigned_abs().pow(2);
+        if diff <= 0 || plane <= radius_sq {
+            self.within_in(&slice[..mid], next, query, radius_sq, found);
+        }
+        if diff >= 0 || plane <= radius_sq {
+            self.within_in(&slice[mid + 1..], next, query, radius_sq, found);
+        }
+    }
+}
+
+/// Arranges `order` so each slice's middle element is the median along `axis` of its points.
+fn build(points: &[Point3], order: &mut [usize], axis: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by_key(mid, |&i| points[i].axis(axis));
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, (axis + 1) % 3);
+    build(points, &mut right[1..], (axis + 1) % 3);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cloud() -> Vec<Point3> {
+        // A deterministic scatter with some repeated coordinates.
+        (0..200)
+            .map(|i: i64| Point3::new(i * 37 % 101, i * 53 % 89 - 40, i * 11 % 7))
+            .collect()
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let points = cloud();
+        let tree = KdTree::new(&points);
+        for query in [Point3::new(50, 0, 3), Point3::new(-10, 100, 0), points[17]] {
+            let mut expected = points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.dist_sq(&query), i))
+                .collect::<Vec<_>>();
+            expected.sort_unstable();
+            expected.truncate(6);
+            assert_eq!(expected, tree.nearest(&query, 6));
+        }
+        assert!(tree.nearest(&points[0], 0).is_empty());
+        assert_eq!(points.len(), tree.nearest(&points[0], 500).len());
+    }
+
+    #[test]
+    fn within_matches_brute_force() {
+        let points = cloud();
+        let tree = KdTree::new(&points);
+        let query = Point3::new(30, -5, 2);
+        let mut found = tree.within(&query, 150);
+        found.sort_unstable();
+        let expected = (0..points.len())
+            .filter(|&i| points[i].dist_sq(&query) <= 150)
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty());
+        assert_eq!(expected, found);
+    }
+}
@@ -16,6 +16,7 @@ pub enum PuzzleAnswer {
     USIZE(usize),
     U32(u32),
     U64(u64),
+    U128(u128),
     Str(String),
 }
 
@@ -25,6 +26,7 @@ impl fmt::Display for PuzzleAnswer {
             PuzzleAnswer::USIZE(u) => write!(f, "{u}"),
             PuzzleAnswer::U32(v) => write!(f, "{v}"),
             PuzzleAnswer::U64(v) => write!(f, "{v}"),
+            PuzzleAnswer::U128(v) => write!(f, "{v}"),
             PuzzleAnswer::Str(s) => write!(f, "{s}"),
         }
     }
@@ -42,6 +44,12 @@ impl From<u64> for PuzzleAnswer {
     }
 }
 
+impl From<u128> for PuzzleAnswer {
+    fn from(v: u128) -> Self {
+        Self::U128(v)
+    }
+}
+
 impl From<String> for PuzzleAnswer {
     fn from(s: String) -> Self {
         Self::Str(s)
@@ -79,6 +87,19 @@ pub enum AdventError {
     UnknownError,
 }
 
+impl AdventError {
+    /// Prefixes a parse error with the 1-based line number for the 0-based `index`. Other
+    /// errors are returned unchanged.
+    pub fn at_line(self, index: usize) -> Self {
+        match self {
+            Self::InputParseError(msg) => {
+                Self::InputParseError(format!("line {}: {msg}", index + 1).into())
+            }
+            other => other,
+        }
+    }
+}
+
 impl From<ParseIntError> for AdventError {
     fn from(err: ParseIntError) -> Self {
         Self::InputParseError(err.to_string().into())
@@ -137,9 +158,29 @@ macro_rules! define_advent_registry {
 }
 
 pub mod arithmetic;
+pub mod automaton;
+pub mod bitgrid;
+pub mod compress;
+pub mod cycle;
+pub mod digits;
 pub mod direction;
+pub mod exact_cover;
+pub mod fasthash;
 pub mod floodfill;
+pub mod geometry;
+pub mod gf2;
+pub mod graph;
+pub mod input;
+pub mod intern;
+pub mod kdtree;
+pub mod memo;
 pub mod number;
+pub mod point3;
+pub mod polyomino;
 pub mod range;
+pub mod ring;
 pub mod rotation;
+pub mod scan;
+pub mod trie;
+pub mod tuple;
 pub mod ufind;
@@ -0,0 +1,260 @@
+//! Memoisation for recursive solvers.
+//!
+//! [`Memo`] is a cache that counts its hits and misses. Recursive code takes `&mut Memo` and calls
+//! [`Memo::get_or_insert_with`], whose closure receives the memo back to make nested calls.
+//! [`Memoized`] bundles a self-recursive closure with its own memo.
+//!
+//! Memos created with [`Memo::labelled`] record their [`MemoStats`] when dropped. The runner can
+//! then print them with [`take_reports`].
+
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::fasthash::FastMap;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+}
+
+impl MemoStats {
+    /// Fraction of lookups answered from the cache, or 0 when nothing was looked up.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "hits={} misses={} size={} hit_rate={:.1}%",
+            self.hits,
+            self.misses,
+            self.size,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+thread_local! {
+    static REPORTS: RefCell<Vec<(&'static str, MemoStats)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Drains the stats recorded by labelled memos on this thread, in the order they were dropped.
+pub fn take_reports() -> Vec<(&'static str, MemoStats)> {
+    REPORTS.with(|r| std::mem::take(&mut *r.borrow_mut()))
+}
+
+/// Cache with hit/miss counters.
+pub struct Memo<K, V> {
+    cache: FastMap<K, V>,
+    hits: u64,
+    misses: u64,
+    label: Option<&'static str>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: FastMap::default(),
+            hits: 0,
+            misses: 0,
+            label: None,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A memo whose stats are recorded under `label` when it is dropped.
+    pub fn labelled(label: &'static str) -> Self {
+        let mut memo = Self::default();
+        memo.label = Some(label);
+        memo
+    }
+
+    /// Returns the cached value for `key`, computing and storing it on a miss.
+    ///
+    /// `compute` gets the memo back so it can recurse through the same cache.
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            self.hits += 1;
+            return v.clone();
+        }
+
+        self.misses += 1;
+        let v = compute(self);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.cache.len(),
+        }
+    }
+
+    /// Empties the cache and resets the counters, so the memo starts a fresh run. A labelled
+    /// memo first records the stats of the run being cleared, as dropping it would.
+    pub fn clear(&mut self) {
+        self.report();
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    fn report(&self) {
+        if let Some(label) = self.label {
+            let stats = MemoStats {
+                hits: self.hits,
+                misses: self.misses,
+                size: self.cache.len(),
+            };
+            REPORTS.with(|r| r.borrow_mut().push((label, stats)));
+        }
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        self.report();
+    }
+}
+
+/// A recursive function bundled with its memo.
+///
+/// The function receives a `recurse` callback for its sub-problems, which goes through the same
+/// cache:
+///
+/// ```
+/// use advent_common::memo::Memoized;
+///
+/// let mut fib = Memoized::new(|recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+///     if n < 2 { n } else { recurse(n - 1) + recurse(n - 2) }
+/// });
+/// assert_eq!(12_586_269_025, fib.call(50));
+/// assert_eq!(51, fib.stats().size);
+/// ```
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            memo: Memo::new(),
+            f,
+        }
+    }
+
+    pub fn labelled(label: &'static str, f: F) -> Self {
+        Self {
+            memo: Memo::labelled(label),
+            f,
+        }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        Self::recurse(&self.f, &mut self.memo, key)
+    }
+
+    fn recurse(f: &F, memo: &mut Memo<K, V>, key: K) -> V {
+        memo.get_or_insert_with(key.clone(), |memo| {
+            f(&mut |k| Self::recurse(f, memo, k), key)
+        })
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.memo.stats()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid_paths(memo: &mut Memo<(u32, u32), u64>, r: u32, c: u32) -> u64 {
+        if r == 0 || c == 0 {
+            return 1;
+        }
+        memo.get_or_insert_with((r, c), |memo| {
+            grid_paths(memo, r - 1, c) + grid_paths(memo, r, c - 1)
+        })
+    }
+
+    #[test]
+    fn recursive_stats() {
+        let mut memo = Memo::new();
+        assert_eq!(184_756, grid_paths(&mut memo, 10, 10));
+
+        let stats = memo.stats();
+        assert_eq!(100, stats.size);
+        assert_eq!(100, stats.misses);
+        assert_eq!(81, stats.hits);
+
+        memo.clear();
+        assert_eq!(MemoStats::default(), memo.stats());
+        assert_eq!(6, grid_paths(&mut memo, 2, 2));
+        assert_eq!(4, memo.stats().misses);
+        assert_eq!(1, memo.stats().hits);
+    }
+
+    #[test]
+    fn labelled_reports_on_drop() {
+        take_reports();
+        {
+            let mut memo = Memo::labelled("squares");
+            for n in [3, 4, 3] {
+                memo.get_or_insert_with(n, |_| n * n);
+            }
+            memo.clear();
+            memo.get_or_insert_with(5, |_| 25);
+        }
+
+        let reports = take_reports();
+        assert_eq!(2, reports.len());
+        assert_eq!("squares", reports[0].0);
+        assert_eq!(
+            MemoStats {
+                hits: 1,
+                misses: 2,
+                size: 2
+            },
+            reports[0].1
+        );
+        assert_eq!(
+            MemoStats {
+                hits: 0,
+                misses: 1,
+                size: 1
+            },
+            reports[1].1
+        );
+    }
+}
@@ -1,19 +1,12 @@
 use std::str::FromStr;
 
 use crate::AdventError;
+use crate::digits::Digits;
+use crate::tuple::{COMMA, TupleParser};
 
-pub fn num_digits(mut n: u32) -> u32 {
-    if n == 0 {
-        return 1;
-    }
-
-    let mut cnt = 0;
-    while n > 0 {
-        n /= 10;
-        cnt += 1;
-    }
-
-    cnt
+/// Number of decimal digits in `n`, see [`Digits::num_digits`].
+pub fn num_digits<T: Digits>(n: T) -> u32 {
+    n.num_digits()
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -58,34 +51,54 @@ impl<K, V> Pair<K, V> {
     }
 }
 
+impl<K: FromStr, V: FromStr> Pair<K, V> {
+    /// Parses two fields split by `parser`'s delimiter.
+    pub fn parse_with(parser: &TupleParser, s: &str) -> Result<Self, AdventError> {
+        let (first, second) = parser.pair(s)?;
+        Ok(Self { first, second })
+    }
+}
+
 impl<K: FromStr, V: FromStr> TryFrom<&str> for Pair<K, V> {
     type Error = AdventError;
 
+    /// Parses `first,second`.
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let mut parts = s.split(',');
-        let first = parts
-            .next()
-            .filter(|&f| !f.is_empty())
-            .ok_or_else(|| AdventError::InputParseError("triple: missing first component".into()))?
-            .parse::<K>()
-            .map_err(|_| {
-                AdventError::InputParseError("unable to parse first argument from string".into())
-            })?;
-        let second = parts
-            .next()
-            .filter(|&s| !s.is_empty())
-            .ok_or_else(|| AdventError::InputParseError("triple: missing second component".into()))?
-            .parse::<V>()
-            .map_err(|_| {
-                AdventError::InputParseError("unable to parse second argument from string".into())
-            })?;
-
-        if parts.next().is_some() {
-            return Err(AdventError::InputParseError(
-                "triple: expected exactly three comma-separated numbers".into(),
-            ));
+        Self::parse_with(&COMMA, s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Triple<A, B, C> {
+    pub first: A,
+    pub second: B,
+    pub third: C,
+}
+
+impl<A, B, C> Triple<A, B, C> {
+    pub fn new(first: A, second: B, third: C) -> Self {
+        Self {
+            first,
+            second,
+            third,
         }
-        Ok(Self { first, second })
+    }
+}
+
+impl<A: FromStr, B: FromStr, C: FromStr> Triple<A, B, C> {
+    /// Parses three fields split by `parser`'s delimiter.
+    pub fn parse_with(parser: &TupleParser, s: &str) -> Result<Self, AdventError> {
+        let (first, second, third) = parser.triple(s)?;
+        Ok(Self::new(first, second, third))
+    }
+}
+
+impl<A: FromStr, B: FromStr, C: FromStr> TryFrom<&str> for Triple<A, B, C> {
+    type Error = AdventError;
+
+    /// Parses `first,second,third`.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::parse_with(&COMMA, s)
     }
 }
 
@@ -113,29 +126,9 @@ impl XYZCoord {
 impl TryFrom<&str> for XYZCoord {
     type Error = AdventError;
 
+    /// Parses `x,y,z`.
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let mut parts = s.split(',');
-        let x = parts
-            .next()
-            .filter(|x| !x.is_empty())
-            .ok_or_else(|| AdventError::InputParseError("triple: missing first component".into()))?
-            .parse::<u64>()?;
-        let y = parts
-            .next()
-            .filter(|y| !y.is_empty())
-            .ok_or_else(|| AdventError::InputParseError("triple: missing second component".into()))?
-            .parse::<u64>()?;
-        let z = parts
-            .next()
-            .filter(|z| !z.is_empty())
-            .ok_or_else(|| AdventError::InputParseError("triple: missing third component".into()))?
-            .parse::<u64>()?;
-
-        if parts.next().is_some() {
-            return Err(AdventError::InputParseError(
-                "triple: expected exactly three comma-separated numbers".into(),
-            ));
-        }
+        let [x, y, z] = COMMA.array(s)?;
         Ok(XYZCoord { x, y, z })
     }
 }
@@ -147,3 +140,34 @@ impl TryFrom<String> for XYZCoord {
         Self::try_from(s.as_str())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tuples() {
+        assert_eq!(
+            Pair::new(3, -4),
+            Pair::<u32, i64>::try_from("3,-4").unwrap()
+        );
+        assert_eq!(
+            Pair::new(2, 'b'),
+            Pair::<u8, char>::parse_with(&TupleParser::new("->"), "2 -> b").unwrap()
+        );
+        assert_eq!(
+            Triple::new(1, 2, 3),
+            Triple::<u8, u8, u8>::try_from("1, 2, 3").unwrap()
+        );
+
+        let err = Pair::<u32, u32>::try_from("1,2,3").unwrap_err().to_string();
+        assert!(!err.contains("triple"), "{err}");
+        assert!(XYZCoord::try_from("1,2").is_err());
+        assert_eq!(
+            14,
+            XYZCoord::try_from("1,2,3")
+                .unwrap()
+                .dist_sq(&XYZCoord::new(0, 0, 0))
+        );
+    }
+}
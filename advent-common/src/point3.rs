@@ -0,0 +1,149 @@
+//! Signed 3D points and vectors.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::AdventError;
+use crate::input::signed_ints_n;
+use crate::number::XYZCoord;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Coordinate along `axis`, where 0, 1 and 2 are x, y and z.
+    pub fn axis(&self, axis: usize) -> i64 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!("axis {axis} out of range for a 3D point"),
+        }
+    }
+
+    pub fn manhattan(&self, other: &Point3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Squared Euclidean distance. Exact while the points are less than about 2^31 apart on
+    /// every axis, e.g. coordinates within ±2^30; further apart it overflows.
+    pub fn dist_sq(&self, other: &Point3) -> u64 {
+        self.x.abs_diff(other.x).pow(2)
+            + self.y.abs_diff(other.y).pow(2)
+            + self.z.abs_diff(other.z).pow(2)
+    }
+
+    pub fn euclidean(&self, other: &Point3) -> f64 {
+        (self.dist_sq(other) as f64).sqrt()
+    }
+
+    pub fn dot(&self, other: &Point3) -> i64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Point3) -> Point3 {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl Add for Point3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i64> for Point3 {
+    type Output = Self;
+
+    fn mul(self, k: i64) -> Self {
+        Self::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl From<XYZCoord> for Point3 {
+    fn from(p: XYZCoord) -> Self {
+        Self::new(p.x as i64, p.y as i64, p.z as i64)
+    }
+}
+
+impl TryFrom<&str> for Point3 {
+    type Error = AdventError;
+
+    /// Reads the three integers of a line such as `-1,2,3` or `<x=-1, y=2, z=3>`.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let [x, y, z] = signed_ints_n(s)?;
+        Ok(Self::new(x, y, z))
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_metrics() {
+        let a = Point3::try_from("1,-2,3").unwrap();
+        let b = Point3::new(4, 2, 3);
+        assert_eq!(Point3::new(5, 0, 6), a + b);
+        assert_eq!(Point3::new(3, 4, 0), b - a);
+        assert_eq!(Point3::new(-2, 4, -6), -a * 2);
+        assert_eq!(7, a.manhattan(&b));
+        assert_eq!(25, a.dist_sq(&b));
+        assert_eq!(5.0, a.euclidean(&b));
+        assert_eq!(
+            Point3::new(0, 0, 1),
+            Point3::new(1, 0, 0).cross(&Point3::new(0, 1, 0))
+        );
+        assert!(Point3::try_from("1,2").is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Polyominoes: shapes made of grid cells that may be rotated and mirrored.
+//!
+//! A [`Polyomino`] keeps its cells sorted and shifted so the topmost row and leftmost column
+//! are 0. Two shapes with the same cells in the same orientation therefore compare equal, and
+//! [`Polyomino::canonical`] extends that to "equal up to rotation and reflection".
+
+use crate::bitgrid::BitGrid;
+use crate::rotation::Rotation;
+
+/// A `(row, col)` cell position.
+pub type Cell = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Polyomino {
+    cells: Vec<Cell>,
+}
+
+impl Polyomino {
+    pub fn new(cells: impl IntoIterator<Item = Cell>) -> Self {
+        let mut cells = cells.into_iter().collect::<Vec<_>>();
+        let min_r = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_c = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        for cell in &mut cells {
+            *cell = (cell.0 - min_r, cell.1 - min_c);
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        Self { cells }
+    }
+
+    /// The set cells of `grid`.
+    pub fn from_grid(grid: &BitGrid) -> Self {
+        Self::new(grid.iter_ones())
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Rows spanned by the shape.
+    pub fn height(&self) -> usize {
+        self.cells.iter().map(|c| c.0 + 1).max().unwrap_or(0)
+    }
+
+    /// Columns spanned by the shape.
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(|c| c.1 + 1).max().unwrap_or(0)
+    }
+
+    /// The shape turned 90° clockwise.
+    pub fn rotated(&self) -> Self {
+        let dims = (self.height(), self.width());
+        Self::new(
+            self.cells
+                .iter()
+                .map(|&cell| Rotation::Clockwise.map_cell(cell, dims)),
+        )
+    }
+
+    /// The shape mirrored left to right.
+    pub fn flipped(&self) -> Self {
+        let w = self.width();
+        Self::new(self.cells.iter().map(|&(r, c)| (r, w - 1 - c)))
+    }
+
+    /// The distinct shapes among the four rotations and their mirror images, sorted. Symmetric
+    /// shapes have fewer than eight.
+    pub fn orientations(&self) -> Vec<Polyomino> {
+        let mut all = Vec::with_capacity(8);
+        let mut shape = self.clone();
+        for _ in 0..4 {
+            all.push(shape.flipped());
+            let next = shape.rotated();
+            all.push(shape);
+            shape = next;
+        }
+        all.sort_unstable();
+        all.dedup();
+        all
+    }
+
+    /// A representative that is the same for every rotation and reflection of this shape.
+    pub fn canonical(&self) -> Polyomino {
+        self.orientations().swap_remove(0)
+    }
+
+    /// Every way to put the shape, in any orientation, fully inside a `rows` x `cols` grid.
+    /// Each placement lists the covered cells in sorted order.
+    pub fn placements(&self, rows: usize, cols: usize) -> Vec<Vec<Cell>> {
+        let mut placements = Vec::new();
+        for shape in self.orientations() {
+            let (h, w) = (shape.height(), shape.width());
+            if h > rows || w > cols {
+                continue;
+            }
+            for dr in 0..=rows - h {
+                for dc in 0..=cols - w {
+                    placements.push(shape.cells.iter().map(|&(r, c)| (r + dr, c + dc)).collect());
+                }
+            }
+        }
+        placements
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shape(rows: &[&str]) -> Polyomino {
+        Polyomino::from_grid(&BitGrid::from_lines(rows, |c| c == '#').unwrap())
+    }
+
+    #[test]
+    fn orientations() {
+        assert_eq!(8, shape(&["#.", "#.", "##"]).orientations().len());
+        assert_eq!(4, shape(&["###", ".#."]).orientations().len());
+        assert_eq!(2, shape(&["####"]).orientations().len());
+        assert_eq!(1, shape(&["##", "##"]).orientations().len());
+
+        let l = shape(&["#.", "#.", "##"]);
+        assert_eq!(shape(&["###", "#.."]), l.rotated());
+        assert_eq!(shape(&[".#", ".#", "##"]), l.flipped());
+        assert_eq!(l.canonical(), l.rotated().flipped().canonical());
+        assert_ne!(l.canonical(), shape(&["##.", ".##"]).canonical());
+    }
+
+    #[test]
+    fn placements() {
+        let l = shape(&["#.", "#.", "##"]);
+        // Eight orientations, each with two positions inside a 3x3 grid.
+        assert_eq!(16, l.placements(3, 3).len());
+        assert_eq!(4, l.placements(2, 3).len());
+        assert!(l.placements(1, 10).is_empty());
+        assert!(
+            l.placements(2, 3)
+                .contains(&vec![(0, 0), (0, 1), (0, 2), (1, 0)])
+        );
+    }
+}
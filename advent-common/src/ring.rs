@@ -0,0 +1,156 @@
+//! Counters that wrap around, like a dial with positions `0..modulus`.
+//!
+//! Moving a counter reports how many single steps landed on zero along the way, which is what
+//! dial and clock puzzles usually ask for. Both directions take O(1) however far they go.
+//! [`Modular`] keeps its modulus at runtime, and [`Ring`] fixes it at compile time.
+
+use std::fmt;
+
+use crate::rotation::Rotation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modular {
+    value: u64,
+    modulus: u64,
+}
+
+impl Modular {
+    /// `value` reduced into `0..modulus`.
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Steps up `steps` times and returns how many of those steps landed on zero.
+    pub fn add(&mut self, steps: u64) -> u64 {
+        let total = self.value as u128 + steps as u128;
+        self.value = (total % self.modulus as u128) as u64;
+        (total / self.modulus as u128) as u64
+    }
+
+    /// Steps down `steps` times and returns how many of those steps landed on zero.
+    pub fn sub(&mut self, steps: u64) -> u64 {
+        // Counting down from `value` is counting up from `-value`, reflected.
+        let reflected = (self.modulus - self.value) % self.modulus;
+        let total = reflected as u128 + steps as u128;
+        self.value = (self.modulus - (total % self.modulus as u128) as u64) % self.modulus;
+        (total / self.modulus as u128) as u64
+    }
+
+    /// [`Modular::add`] for a clockwise turn and [`Modular::sub`] for a counter-clockwise one.
+    pub fn turn(&mut self, rotation: Rotation, steps: u64) -> u64 {
+        match rotation {
+            Rotation::Clockwise => self.add(steps),
+            Rotation::CounterClockwise => self.sub(steps),
+        }
+    }
+}
+
+impl fmt::Display for Modular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+/// A [`Modular`] counter whose modulus is the type parameter `N`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ring<const N: u64> {
+    value: u64,
+}
+
+impl<const N: u64> Ring<N> {
+    pub const fn new(value: u64) -> Self {
+        assert!(N > 0, "modulus must be positive");
+        Self { value: value % N }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    fn apply(&mut self, step: impl FnOnce(&mut Modular) -> u64) -> u64 {
+        let mut m = Modular::from(*self);
+        let hits = step(&mut m);
+        self.value = m.value;
+        hits
+    }
+
+    /// See [`Modular::add`].
+    pub fn add(&mut self, steps: u64) -> u64 {
+        self.apply(|m| m.add(steps))
+    }
+
+    /// See [`Modular::sub`].
+    pub fn sub(&mut self, steps: u64) -> u64 {
+        self.apply(|m| m.sub(steps))
+    }
+
+    /// See [`Modular::turn`].
+    pub fn turn(&mut self, rotation: Rotation, steps: u64) -> u64 {
+        self.apply(|m| m.turn(rotation, steps))
+    }
+}
+
+impl<const N: u64> From<Ring<N>> for Modular {
+    fn from(ring: Ring<N>) -> Self {
+        Modular {
+            value: ring.value,
+            modulus: N,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zero_hits() {
+        let mut m = Modular::new(50, 100);
+        assert_eq!(1, m.add(68));
+        assert_eq!(18, m.value());
+        assert_eq!(0, m.sub(18 - 1));
+        assert_eq!(1, m.sub(1));
+        assert_eq!(0, m.value());
+        // Leaving zero does not count, and each full lap hits it once.
+        assert_eq!(10, m.sub(1000));
+        assert_eq!(0, m.value());
+        assert_eq!(2, m.add(250));
+        assert_eq!(50, m.value());
+        assert_eq!(u64::MAX / 100, Modular::new(0, 100).add(u64::MAX));
+    }
+
+    #[test]
+    fn matches_stepping() {
+        let mut ring = Ring::<7>::new(3);
+        let mut slow = 3u64;
+        for (i, steps) in [0, 1, 6, 7, 15, 3, 20].into_iter().enumerate() {
+            let rotation = if i % 2 == 0 {
+                Rotation::Clockwise
+            } else {
+                Rotation::CounterClockwise
+            };
+            let mut hits = 0;
+            for _ in 0..steps {
+                slow = match rotation {
+                    Rotation::Clockwise => (slow + 1) % 7,
+                    Rotation::CounterClockwise => (slow + 6) % 7,
+                };
+                hits += u64::from(slow == 0);
+            }
+            assert_eq!(hits, ring.turn(rotation, steps));
+            assert_eq!(slow, ring.value());
+        }
+    }
+}
@@ -1,4 +1,180 @@
+//! Quarter turns, and the grid and direction transforms built on them.
+//!
+//! Grids are row-major `Vec<Vec<T>>`s as produced by [`crate::floodfill::construct_grid`]. A
+//! `rows` x `cols` grid turned a quarter becomes `cols` x `rows`, with row 0 at the top in both.
+
+use crate::direction::{CardinalDirection, OrdinalDirection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rotation {
     Clockwise,
     CounterClockwise,
 }
+
+impl Rotation {
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Clockwise => Self::CounterClockwise,
+            Self::CounterClockwise => Self::Clockwise,
+        }
+    }
+
+    /// Where cell `(r, c)` of a `rows` x `cols` grid ends up after the turn.
+    pub fn map_cell(self, (r, c): (usize, usize), (rows, cols): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Clockwise => (c, rows - 1 - r),
+            Self::CounterClockwise => (cols - 1 - c, r),
+        }
+    }
+
+    /// `grid` turned a quarter in this direction.
+    pub fn rotate<T: Clone>(self, grid: &[Vec<T>]) -> Vec<Vec<T>> {
+        let (rows, cols) = dims(grid);
+        let back = self.inverse();
+        (0..cols)
+            .map(|r| {
+                (0..rows)
+                    .map(|c| {
+                        let (or, oc) = back.map_cell((r, c), (cols, rows));
+                        grid[or][oc].clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn dims<T>(grid: &[Vec<T>]) -> (usize, usize) {
+    (grid.len(), grid.first().map_or(0, Vec::len))
+}
+
+/// Rows become columns: `transpose(grid)[c][r] == grid[r][c]`.
+pub fn transpose<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    let (rows, cols) = dims(grid);
+    (0..cols)
+        .map(|c| (0..rows).map(|r| grid[r][c].clone()).collect())
+        .collect()
+}
+
+/// Mirrors each row left to right.
+pub fn flip_horizontal<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    grid.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// Mirrors the grid top to bottom.
+pub fn flip_vertical<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    grid.iter().rev().cloned().collect()
+}
+
+/// The diagonals running down and to the right, starting from the bottom-left corner. Turning
+/// the grid first gives the other diagonal direction.
+pub fn diagonals<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    let (rows, cols) = dims(grid);
+    (0..rows + cols.saturating_sub(1))
+        .map(|d| {
+            // Diagonal `d` starts at row `rows - 1 - d` when that is on the grid, else on row 0.
+            let (r0, c0) = if d < rows {
+                (rows - 1 - d, 0)
+            } else {
+                (0, d + 1 - rows)
+            };
+            (0..)
+                .map(|i| (r0 + i, c0 + i))
+                .take_while(|&(r, c)| r < rows && c < cols)
+                .map(|(r, c)| grid[r][c].clone())
+                .collect()
+        })
+        .collect()
+}
+
+impl CardinalDirection {
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        match (rotation, self) {
+            (Rotation::Clockwise, Self::North) | (Rotation::CounterClockwise, Self::South) => {
+                Self::East
+            }
+            (Rotation::Clockwise, Self::East) | (Rotation::CounterClockwise, Self::West) => {
+                Self::South
+            }
+            (Rotation::Clockwise, Self::South) | (Rotation::CounterClockwise, Self::North) => {
+                Self::West
+            }
+            (Rotation::Clockwise, Self::West) | (Rotation::CounterClockwise, Self::East) => {
+                Self::North
+            }
+        }
+    }
+}
+
+impl OrdinalDirection {
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        match (rotation, self) {
+            (Rotation::Clockwise, Self::NorthWest)
+            | (Rotation::CounterClockwise, Self::SouthEast) => Self::NorthEast,
+            (Rotation::Clockwise, Self::NorthEast)
+            | (Rotation::CounterClockwise, Self::SouthWest) => Self::SouthEast,
+            (Rotation::Clockwise, Self::SouthEast)
+            | (Rotation::CounterClockwise, Self::NorthWest) => Self::SouthWest,
+            (Rotation::Clockwise, Self::SouthWest)
+            | (Rotation::CounterClockwise, Self::NorthEast) => Self::NorthWest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|r| r.chars().collect()).collect()
+    }
+
+    #[test]
+    fn grid_transforms() {
+        let g = grid(&["abc", "def"]);
+        assert_eq!(grid(&["da", "eb", "fc"]), Rotation::Clockwise.rotate(&g));
+        assert_eq!(
+            grid(&["cf", "be", "ad"]),
+            Rotation::CounterClockwise.rotate(&g)
+        );
+        assert_eq!(
+            g,
+            Rotation::CounterClockwise.rotate(&Rotation::Clockwise.rotate(&g))
+        );
+        assert_eq!(grid(&["ad", "be", "cf"]), transpose(&g));
+        assert_eq!(grid(&["cba", "fed"]), flip_horizontal(&g));
+        assert_eq!(grid(&["def", "abc"]), flip_vertical(&g));
+        assert_eq!(grid(&["d", "ae", "bf", "c"]), diagonals(&g));
+
+        let (rows, cols) = (2, 3);
+        for rotation in [Rotation::Clockwise, Rotation::CounterClockwise] {
+            let turned = rotation.rotate(&g);
+            for (r, row) in g.iter().enumerate() {
+                for (c, &ch) in row.iter().enumerate() {
+                    let (nr, nc) = rotation.map_cell((r, c), (rows, cols));
+                    assert_eq!(ch, turned[nr][nc]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn direction_rotation() {
+        use CardinalDirection::*;
+        assert_eq!(East, North.rotate(Rotation::Clockwise));
+        assert_eq!(West, North.rotate(Rotation::CounterClockwise));
+        for d in [North, East, South, West] {
+            assert_eq!(
+                d,
+                d.rotate(Rotation::Clockwise)
+                    .rotate(Rotation::CounterClockwise)
+            );
+        }
+        assert_eq!(
+            OrdinalDirection::SouthWest,
+            OrdinalDirection::SouthEast.rotate(Rotation::Clockwise)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Parsing lines against `format!`-style patterns.
+//!
+//! [`scan!`](crate::scan!) matches a line such as `Button A: X+94, Y+34` against a pattern like
+//! `"Button {}: X+{}, Y+{}"` and parses each `{}` field with [`FromStr`]. It can build a tuple of
+//! explicit types or fill the fields of a struct:
+//!
+//! ```
+//! use advent_common::scan;
+//!
+//! let (button, x, y) = scan!("Button {}: X+{}, Y+{}", "Button A: X+94, Y+34" => char, u64, u64)?;
+//! assert_eq!(('A', 94, 34), (button, x, y));
+//!
+//! struct Prize { x: u64, y: u64 }
+//! let prize = scan!("Prize: X={}, Y={}", "Prize: X=8400, Y=5400" => Prize { x, y })?;
+//! assert_eq!((8400, 5400), (prize.x, prize.y));
+//!
+//! let err = scan!("Prize: X={}, Y={}", "Prize: X=8400 Y=5400" => u64, u64).unwrap_err();
+//! assert_eq!(
+//!     "expected \", Y=\" after column 10 of \"Prize: X=8400 Y=5400\"",
+//!     err.to_string()
+//! );
+//! # Ok::<(), advent_common::AdventError>(())
+//! ```
+//!
+//! Each field takes the shortest text that lets the next piece of literal text match, and the
+//! last field takes the rest of the line. `{{` and `}}` stand for literal braces. Fields must be
+//! separated by literal text, and the number of fields must match the number of types or struct
+//! fields. Breaking either rule is a compile-time error.
+
+use std::any::type_name;
+use std::str::FromStr;
+
+use crate::AdventError;
+
+enum Piece {
+    Literal(String),
+    Field,
+}
+
+fn pieces(pattern: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('}')) => {
+                chars.next();
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Field);
+            }
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                literal.push(c);
+            }
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    pieces
+}
+
+/// Number of `{}` fields in `pattern`. Panics, at compile time when evaluated in a const, if two
+/// fields are not separated by literal text.
+#[doc(hidden)]
+pub const fn field_count(pattern: &str) -> usize {
+    let bytes = pattern.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    let mut previous_field_end = usize::MAX;
+    while i < bytes.len() {
+        if i + 1 < bytes.len() && bytes[i] == bytes[i + 1] && (bytes[i] == b'{' || bytes[i] == b'}')
+        {
+            i += 2;
+        } else if i + 1 < bytes.len() && bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            assert!(
+                previous_field_end != i,
+                "scan! fields must be separated by literal text"
+            );
+            count += 1;
+            i += 2;
+            previous_field_end = i;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+/// Splits `line` into the text matched by each field of `pattern`.
+#[doc(hidden)]
+pub fn fields<'l>(pattern: &str, line: &'l str) -> Result<Vec<&'l str>, AdventError> {
+    let mismatch = |expected: &str, at: usize| {
+        AdventError::InputParseError(
+            format!("expected {expected:?} at column {} of {line:?}", at + 1).into(),
+        )
+    };
+
+    let pieces = pieces(pattern);
+    let mut fields = Vec::new();
+    let mut at = 0;
+    for (i, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Literal(literal) => {
+                if !line[at..].starts_with(literal.as_str()) {
+                    return Err(mismatch(literal, at));
+                }
+                at += literal.len();
+            }
+            Piece::Field => match pieces.get(i + 1) {
+                Some(Piece::Literal(next)) => {
+                    let Some(len) = line[at..].find(next.as_str()) else {
+                        return Err(AdventError::InputParseError(
+                            format!("expected {next:?} after column {} of {line:?}", at + 1).into(),
+                        ));
+                    };
+                    fields.push(&line[at..at + len]);
+                    at += len;
+                }
+                _ => {
+                    fields.push(&line[at..]);
+                    at = line.len();
+                }
+            },
+        }
+    }
+
+    if at < line.len() {
+        return Err(AdventError::InputParseError(
+            format!("unexpected {:?} at the end of {line:?}", &line[at..]).into(),
+        ));
+    }
+    Ok(fields)
+}
+
+/// Matches `line` against `pattern` and hands the field texts to `build`.
+#[doc(hidden)]
+pub fn build<'l, T>(
+    pattern: &str,
+    line: &'l str,
+    build: impl FnOnce(Vec<&'l str>) -> Result<T, AdventError>,
+) -> Result<T, AdventError> {
+    build(fields(pattern, line)?)
+}
+
+/// Parses field `index` (0-based) of a [`fields`] result.
+#[doc(hidden)]
+pub fn parse_field<T: FromStr>(
+    pattern: &str,
+    fields: &[&str],
+    index: usize,
+) -> Result<T, AdventError> {
+    fields[index].parse().map_err(|_| {
+        AdventError::InputParseError(
+            format!(
+                "field {} of {pattern:?}: {:?} is not a valid {}",
+                index + 1,
+                fields[index],
+                type_name::<T>()
+            )
+            .into(),
+        )
+    })
+}
+
+/// Matches a line against a `format!`-style pattern, see the [module docs](crate::scan).
+///
+/// Evaluates to `Result<_, AdventError>`. A pattern whose field count does not match the target
+/// fails to compile:
+///
+/// ```compile_fail
+/// let pair = advent_common::scan!("{}-{}", "3-5" => u32);
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($pattern:literal, $line:expr => $name:ident { $($field:ident),+ $(,)? }) => {
+        $crate::scan!(@fields $pattern, $line, [$($field)+], |fields| {
+            let mut index = 0;
+            Ok($name {
+                $($field: {
+                    index += 1;
+                    $crate::scan::parse_field($pattern, &fields, index - 1)?
+                },)+
+            })
+        })
+    };
+    ($pattern:literal, $line:expr => $($ty:ty),+ $(,)?) => {
+        $crate::scan!(@fields $pattern, $line, [$($ty)+], |fields| {
+            let mut index = 0;
+            Ok(($({
+                index += 1;
+                $crate::scan::parse_field::<$ty>($pattern, &fields, index - 1)?
+            },)+))
+        })
+    };
+    (@fields $pattern:literal, $line:expr, [$($item:tt)+], |$fields:ident| $build:block) => {{
+        const _: () = assert!(
+            $crate::scan::field_count($pattern) == [$(stringify!($item)),+].len(),
+            "scan! pattern and target have a different number of fields"
+        );
+        $crate::scan::build($pattern, $line, |$fields| $build)
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::AdventError;
+
+    #[test]
+    fn tuple_and_struct_targets() -> Result<(), AdventError> {
+        let robot = scan!("p={},{} v={},{}", "p=0,4 v=3,-3" => i64, i64, i64, i64)?;
+        assert_eq!((0, 4, 3, -3), robot);
+
+        #[derive(Debug, PartialEq)]
+        struct Edge {
+            from: String,
+            to: String,
+            weight: u32,
+        }
+        let edge = scan!("{} -> {} [{}]", "a -> b [7]" => Edge { from, to, weight })?;
+        assert_eq!(
+            Edge {
+                from: "a".into(),
+                to: "b".into(),
+                weight: 7
+            },
+            edge
+        );
+
+        let (text,) = scan!("{{{}}}", "{braced}" => String)?;
+        assert_eq!("braced", text);
+        Ok(())
+    }
+
+    #[test]
+    fn mismatch_errors() {
+        let message = |r: Result<(u32, u32), AdventError>| r.unwrap_err().to_string();
+        assert_eq!(
+            "expected \", Y=\" after column 3 of \"X=1, Z=2\"",
+            message(scan!("X={}, Y={}", "X=1, Z=2" => u32, u32))
+        );
+        assert_eq!(
+            "expected \"X=\" at column 1 of \"x=1, Y=2\"",
+            message(scan!("X={}, Y={}", "x=1, Y=2" => u32, u32))
+        );
+        assert_eq!(
+            "field 2 of \"X={}, Y={}\": \"-2\" is not a valid u32",
+            message(scan!("X={}, Y={}", "X=1, Y=-2" => u32, u32))
+        );
+        assert_eq!(
+            "unexpected \"!\" at the end of \"1-2!!\"",
+            message(scan!("{}-{}!", "1-2!!" => u32, u32))
+        );
+    }
+}
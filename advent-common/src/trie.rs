@@ -0,0 +1,145 @@
+//! Prefix tree over byte strings.
+//!
+//! [`Trie::prefixes_at`] lists every stored word that starts at a given position of a text in a
+//! single walk down the tree. Its cost depends on the length of the longest match rather than
+//! on how many words are stored.
+
+/// One node per distinct prefix. Children are few in practice, so they are kept in a small
+/// unsorted list rather than a full byte table.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: Vec<(u8, usize)>,
+    terminal: bool,
+}
+
+impl Node {
+    fn child(&self, b: u8) -> Option<usize> {
+        self.children
+            .iter()
+            .find(|&&(c, _)| c == b)
+            .map(|&(_, next)| next)
+    }
+}
+
+/// Set of words supporting prefix queries.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    words: usize,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            words: 0,
+        }
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `word`, returning false if it was already present.
+    pub fn insert(&mut self, word: impl AsRef<[u8]>) -> bool {
+        let mut node = 0;
+        for &b in word.as_ref() {
+            node = match self.nodes[node].child(b) {
+                Some(next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.push((b, next));
+                    next
+                }
+            };
+        }
+
+        let added = !self.nodes[node].terminal;
+        self.nodes[node].terminal = true;
+        self.words += usize::from(added);
+        added
+    }
+
+    pub fn contains(&self, word: impl AsRef<[u8]>) -> bool {
+        let mut node = 0;
+        for &b in word.as_ref() {
+            match self.nodes[node].child(b) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        self.nodes[node].terminal
+    }
+
+    /// Number of distinct words.
+    pub fn len(&self) -> usize {
+        self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    /// Lengths of the stored words that `text[i..]` starts with, shortest first.
+    pub fn prefixes_at<'a>(
+        &'a self,
+        text: &'a (impl AsRef<[u8]> + ?Sized),
+        i: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let rest = &text.as_ref()[i..];
+        let mut node = Some(0);
+        let mut len = 0;
+        std::iter::from_fn(move || {
+            while let Some(current) = node {
+                let matched = len;
+                node = rest.get(len).and_then(|&b| self.nodes[current].child(b));
+                len += 1;
+                if matched > 0 && self.nodes[current].terminal {
+                    return Some(matched);
+                }
+            }
+            None
+        })
+    }
+}
+
+impl<W: AsRef<[u8]>> FromIterator<W> for Trie {
+    fn from_iter<I: IntoIterator<Item = W>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for word in iter {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_contains() {
+        let mut trie = Trie::from_iter(["r", "wr", "b", "br"]);
+        assert_eq!(4, trie.len());
+        assert!(!trie.insert("br"));
+        assert!(trie.insert("bwu"));
+        assert!(trie.contains("bwu"));
+        assert!(!trie.contains("bw"));
+        assert!(!trie.contains(""));
+    }
+
+    #[test]
+    fn prefixes() {
+        let trie = Trie::from_iter(["r", "wr", "b", "br", "brw", "brwrr"]);
+        assert_eq!(
+            vec![1, 2, 3, 5],
+            trie.prefixes_at("brwrr", 0).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![2], trie.prefixes_at("brwrr", 2).collect::<Vec<_>>());
+        assert_eq!(0, trie.prefixes_at("brwrr", 5).count());
+        assert_eq!(0, trie.prefixes_at("gbr", 0).count());
+    }
+}
@@ -0,0 +1,132 @@
+//! Fixed-size tuples of delimited fields, such as `3,4` or `1 x 2 x 3`.
+//!
+//! A [`TupleParser`] splits on one delimiter, optionally trims each field, insists on exactly
+//! the number of fields asked for, and parses each with [`FromStr`]. Errors name the field
+//! that failed and the whole input.
+
+use std::any::type_name;
+use std::str::FromStr;
+
+use crate::AdventError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TupleParser<'d> {
+    delimiter: &'d str,
+    trim: bool,
+}
+
+/// Comma-separated fields with surrounding whitespace ignored.
+pub const COMMA: TupleParser<'static> = TupleParser::new(",");
+
+impl<'d> TupleParser<'d> {
+    /// Parser splitting on `delimiter` and trimming fields.
+    pub const fn new(delimiter: &'d str) -> Self {
+        Self {
+            delimiter,
+            trim: true,
+        }
+    }
+
+    /// Whether whitespace around each field is ignored.
+    pub const fn trim(self, trim: bool) -> Self {
+        Self { trim, ..self }
+    }
+
+    /// Exactly `N` non-empty fields of `s`.
+    pub fn fields<'s, const N: usize>(&self, s: &'s str) -> Result<[&'s str; N], AdventError> {
+        let fields = s
+            .split(self.delimiter)
+            .map(|f| if self.trim { f.trim() } else { f })
+            .collect::<Vec<_>>();
+        let count = fields.len();
+        let fields: [&str; N] = fields.try_into().map_err(|_| {
+            AdventError::InputParseError(
+                format!(
+                    "expected {N} fields separated by {:?} in {s:?}, found {count}",
+                    self.delimiter
+                )
+                .into(),
+            )
+        })?;
+        if let Some(i) = fields.iter().position(|f| f.is_empty()) {
+            return Err(AdventError::InputParseError(
+                format!("field {} of {s:?} is empty", i + 1).into(),
+            ));
+        }
+        Ok(fields)
+    }
+
+    /// `N` fields of `s`, all parsed as `T`.
+    pub fn array<T: FromStr, const N: usize>(&self, s: &str) -> Result<[T; N], AdventError> {
+        let fields = self.fields::<N>(s)?;
+        let mut values = Vec::with_capacity(N);
+        for (i, field) in fields.iter().enumerate() {
+            values.push(parse_field(s, i, field)?);
+        }
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("exactly {N} fields were parsed")))
+    }
+
+    pub fn pair<A: FromStr, B: FromStr>(&self, s: &str) -> Result<(A, B), AdventError> {
+        let [a, b] = self.fields(s)?;
+        Ok((parse_field(s, 0, a)?, parse_field(s, 1, b)?))
+    }
+
+    pub fn triple<A: FromStr, B: FromStr, C: FromStr>(
+        &self,
+        s: &str,
+    ) -> Result<(A, B, C), AdventError> {
+        let [a, b, c] = self.fields(s)?;
+        Ok((
+            parse_field(s, 0, a)?,
+            parse_field(s, 1, b)?,
+            parse_field(s, 2, c)?,
+        ))
+    }
+}
+
+fn parse_field<T: FromStr>(s: &str, i: usize, field: &str) -> Result<T, AdventError> {
+    field.parse().map_err(|_| {
+        AdventError::InputParseError(
+            format!(
+                "field {} of {s:?}: {field:?} is not a valid {}",
+                i + 1,
+                type_name::<T>()
+            )
+            .into(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_tuples() {
+        assert_eq!((3, -4), COMMA.pair::<u8, i32>(" 3, -4").unwrap());
+        assert_eq!(
+            (1, 'x', "ok".to_string()),
+            COMMA.triple::<u8, char, String>("1,x,ok").unwrap()
+        );
+        assert_eq!(
+            [2, 3, 4],
+            TupleParser::new("x").array::<u32, 3>("2x3x4").unwrap()
+        );
+        assert_eq!(
+            ["a ", " b"],
+            COMMA.trim(false).fields::<2>("a , b").unwrap()
+        );
+    }
+
+    #[test]
+    fn error_messages() {
+        let err = |r: Result<(u8, u8), AdventError>| r.unwrap_err().to_string();
+        assert!(err(COMMA.pair("1,2,3")).contains("expected 2 fields separated by \",\""));
+        assert!(err(COMMA.pair("1,")).contains("field 2 of \"1,\" is empty"));
+        assert!(
+            err(COMMA.pair("1,300")).contains("field 2 of \"1,300\": \"300\" is not a valid u8")
+        );
+    }
+}
@@ -32,12 +32,13 @@ impl UnionFind {
         p
     }
 
-    pub fn connect(&mut self, n1: usize, n2: usize) {
+    /// Merges the sets of `n1` and `n2`, returning false if they were already one set.
+    pub fn connect(&mut self, n1: usize, n2: usize) -> bool {
         let p1 = self.find(n1);
         let p2 = self.find(n2);
 
         if p1 == p2 {
-            return;
+            return false;
         }
 
         if self.sizes[p1] > self.sizes[p2] {
@@ -47,6 +48,7 @@ impl UnionFind {
             self.parents[p2] = p1;
             self.sizes[p1] += self.sizes[p2];
         }
+        true
     }
 
     /// Partitions `{0 .. self.size}` into disjoint sets (each inner vec sorted).
@@ -87,3 +89,49 @@ impl UnionFind {
         self.sizes.clone()
     }
 }
+
+/// Kruskal's algorithm: the edges of a minimum spanning forest over nodes `0..n`, in the order
+/// they were added, which is by increasing weight with ties broken by endpoints. `edges` are
+/// `(weight, u, v)` and need not be sorted. Returns the forest's edges along with the
+/// [`UnionFind`] left behind, whose groups are the connected components.
+pub fn minimum_spanning_forest<W: Ord + Copy>(
+    n: usize,
+    edges: impl IntoIterator<Item = (W, usize, usize)>,
+) -> (Vec<(W, usize, usize)>, UnionFind) {
+    let mut edges = edges.into_iter().collect::<Vec<_>>();
+    edges.sort_unstable();
+
+    let mut uf = UnionFind::new(n);
+    let mut forest = Vec::with_capacity(n.saturating_sub(1));
+    for (w, u, v) in edges {
+        if uf.connect(u, v) {
+            forest.push((w, u, v));
+            if forest.len() + 1 == n {
+                break;
+            }
+        }
+    }
+    (forest, uf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spanning_forest() {
+        let edges = [
+            (4, 0, 1),
+            (1, 1, 2),
+            (3, 0, 2),
+            (2, 2, 3),
+            (7, 4, 5),
+            (5, 1, 3),
+        ];
+        let (forest, mut uf) = minimum_spanning_forest(6, edges);
+        assert_eq!(vec![(1, 1, 2), (2, 2, 3), (3, 0, 2), (7, 4, 5)], forest);
+        assert_eq!(2, uf.component_count());
+        assert!(!uf.connect(0, 3));
+        assert!(uf.connect(3, 4));
+    }
+}
@@ -13,6 +13,10 @@ struct Args {
     /// Part (1 or 2)
     #[arg(short, long)]
     part: u8,
+
+    /// Print solver statistics, such as memo cache hits, after the answer
+    #[arg(short, long)]
+    verbose: bool,
 }
 
 fn main() {
@@ -29,7 +33,14 @@ fn main() {
         std::process::exit(1);
     }
 
-    match solutions::run(args.year, args.date, args.part) {
+    let result = solutions::run(args.year, args.date, args.part);
+    if args.verbose {
+        for (label, stats) in advent_common::memo::take_reports() {
+            eprintln!("memo {label}: {stats}");
+        }
+    }
+
+    match result {
         Ok(res) => println!(
             "Year {}, Day {}, Part {}: {}",
             args.year, args.date, args.part, res
@@ -186,7 +186,7 @@ mod test {
 
     #[test]
     fn sample() {
-        let lines = vec![
+        let lines = [
             "89010123", "78121874", "87430965", "96549874", "45678903", "32019012", "01329801",
             "10456732",
         ];
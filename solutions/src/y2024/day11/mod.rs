@@ -1,10 +1,11 @@
 use crate::{AdventError, AdventProblem};
-use std::collections::HashMap;
+use advent_common::digits::Digits;
+use advent_common::memo::Memo;
 
 pub struct Day11;
 
 impl AdventProblem for Day11 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let mut nums = lines[0]
             .split(" ")
@@ -19,35 +20,34 @@ impl AdventProblem for Day11 {
             nums = next;
         }
 
-        Ok(nums.len() as u32)
+        Ok(nums.len() as u64)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut stone_counts: HashMap<u64, usize> = lines[0]
+        let mut memo = Memo::labelled("2024 day11 stone counts");
+
+        let total = lines[0]
             .split(" ")
             .map(|n| n.parse().expect("numeric value"))
-            .fold(HashMap::new(), |mut map, n| {
-                *map.entry(n).or_default() += 1;
-                map
-            });
-
-        for _ in 0..75 {
-            stone_counts = stone_counts.iter().fold(
-                HashMap::<u64, usize>::new(),
-                |mut counts, (&stone, &count)| {
-                    let new_stones = blink(stone);
-                    for s in new_stones {
-                        *counts.entry(s).or_default() += count;
-                    }
-                    counts
-                },
-            );
-        }
+            .map(|n| stone_count(n, 75, &mut memo))
+            .sum();
 
-        println!("{}", stone_counts.values().sum::<usize>());
+        Ok(total)
+    }
+}
 
-        Ok(0)
+/// Number of stones `stone` has split into after `blinks` more blinks.
+fn stone_count(stone: u64, blinks: u32, memo: &mut Memo<(u64, u32), u64>) -> u64 {
+    if blinks == 0 {
+        return 1;
     }
+
+    memo.get_or_insert_with((stone, blinks), |memo| {
+        blink(stone)
+            .into_iter()
+            .map(|s| stone_count(s, blinks - 1, memo))
+            .sum()
+    })
 }
 
 fn blink(n: u64) -> Vec<u64> {
@@ -55,10 +55,9 @@ fn blink(n: u64) -> Vec<u64> {
         return vec![1];
     }
 
-    let digits = n.to_string();
-    if digits.len().is_multiple_of(2) {
-        let first = digits[..digits.len() / 2].parse::<u64>().unwrap();
-        let second = digits[digits.len() / 2..].parse::<u64>().unwrap();
+    let digits = n.num_digits();
+    if digits.is_multiple_of(2) {
+        let (first, second) = n.split_at(digits / 2);
         vec![first, second]
     } else {
         vec![n * 2024]
@@ -78,4 +77,17 @@ mod test {
         assert_eq!(vec![10, 0], blink(1000));
         assert_eq!(vec![1], blink(0));
     }
+
+    #[test]
+    fn sample_counts() {
+        let mut memo = Memo::new();
+        assert_eq!(
+            22,
+            stone_count(125, 6, &mut memo) + stone_count(17, 6, &mut memo)
+        );
+        assert_eq!(
+            55312,
+            stone_count(125, 25, &mut memo) + stone_count(17, 25, &mut memo)
+        );
+    }
 }
@@ -1,11 +1,13 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::input::sections;
+use advent_common::scan;
 
 pub struct Day13;
 
 impl AdventProblem for Day13 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let games = parse_lines(&lines, 0);
+        let games = parse_lines(&lines, 0)?;
         let total = games
             .iter()
             .map(|g| match g.solve() {
@@ -19,7 +21,7 @@ impl AdventProblem for Day13 {
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let games = parse_lines(&lines, 10000000000000);
+        let games = parse_lines(&lines, 10000000000000)?;
         let total = games
             .iter()
             .map(|g| match g.solve() {
@@ -33,44 +35,34 @@ impl AdventProblem for Day13 {
     }
 }
 
-macro_rules! regex {
-    ($re:literal $(,)?) => {{
-        use {regex::Regex, std::sync::OnceLock};
-
-        static RE: OnceLock<Regex> = OnceLock::new();
-        RE.get_or_init(|| Regex::new($re).unwrap())
-    }};
-}
-
-fn parse_lines(lines: &[String], offset: u64) -> Vec<Game> {
-    let mut i = 0;
-    let n = lines.len();
-
-    let button_a_regex = regex!("Button A: X\\+(\\d+), Y\\+(\\d+)");
-    let button_b_regex = regex!("Button B: X\\+(\\d+), Y\\+(\\d+)");
-    let prize_regex = regex!("Prize: X=(\\d+), Y=(\\d+)");
-
-    let mut games = Vec::new();
-
-    while i < n {
-        let (_, [x_a, y_a]) = button_a_regex.captures(&lines[i]).unwrap().extract();
-        let (_, [x_b, y_b]) = button_b_regex.captures(&lines[i + 1]).unwrap().extract();
-        let (_, [p_a, p_b]) = prize_regex.captures(&lines[i + 2]).unwrap().extract();
-
-        let (x_a, y_a) = (x_a.parse().unwrap(), y_a.parse().unwrap());
-        let (x_b, y_b) = (x_b.parse().unwrap(), y_b.parse().unwrap());
-        let (p_a, p_b) = (p_a.parse::<u64>().unwrap(), p_b.parse::<u64>().unwrap());
-
-        games.push(Game {
-            button_a: (x_a, y_a),
-            button_b: (x_b, y_b),
-            prize: (p_a + offset, p_b + offset),
-        });
-
-        i += 4;
-    }
-
-    games
+fn parse_lines(lines: &[String], offset: u64) -> Result<Vec<Game>, AdventError> {
+    sections(lines)
+        .iter()
+        .map(|section| {
+            let [a, b, prize] = section.lines else {
+                return Err(AdventError::InputParseError(
+                    format!(
+                        "line {}: expected two buttons and a prize",
+                        section.start + 1
+                    )
+                    .into(),
+                ));
+            };
+
+            let button_a = scan!("Button A: X+{}, Y+{}", a => u64, u64)
+                .map_err(|e| e.at_line(section.start))?;
+            let button_b = scan!("Button B: X+{}, Y+{}", b => u64, u64)
+                .map_err(|e| e.at_line(section.start + 1))?;
+            let (p_a, p_b) = scan!("Prize: X={}, Y={}", prize => u64, u64)
+                .map_err(|e| e.at_line(section.start + 2))?;
+
+            Ok(Game {
+                button_a,
+                button_b,
+                prize: (p_a + offset, p_b + offset),
+            })
+        })
+        .collect()
 }
 
 struct Game {
@@ -1,4 +1,5 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::cycle::brent;
 use std::collections::{HashMap, HashSet};
 
 pub struct Day14;
@@ -26,54 +27,60 @@ impl AdventProblem for Day14 {
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut robots = lines.iter().map(|s| parse_line(s)).collect::<Vec<_>>();
+        let robots = lines.iter().map(|s| parse_line(s)).collect::<Vec<_>>();
 
-        for iter in 1..=10000 {
-            let mut positions = HashSet::new();
-            for robot in robots.iter_mut() {
-                robot.move_spaces(1, GRID_WIDTH, GRID_HEIGHT);
-                positions.insert(robot.pos);
-            }
+        // Every robot wraps around the grid on a fixed schedule, so the whole layout repeats and
+        // only the first period needs searching.
+        let period = brent(robots.clone(), |r: &Vec<Robot>| step_robots(r)).period;
 
-            let mut manhattan_dist = 0;
-            for p1 in positions.iter() {
-                for p2 in positions.iter() {
-                    manhattan_dist += i64::abs(p1.0 - p2.0);
-                    manhattan_dist += i64::abs(p1.1 - p2.1);
-                }
-            }
-            println!("Iter={}, manhattan_dist={}", iter, manhattan_dist);
-            if manhattan_dist < 10_000_000 {
-                println!("Found christmas tree at iter iter={}", iter);
-                break;
-            }
-        }
+        let mut state = robots;
+        for iter in 1..=period {
+            state = step_robots(&state);
 
-        let positions: HashSet<(i64, i64)> =
-            robots
-                .iter()
-                .map(|r| r.pos)
-                .fold(HashSet::new(), |mut positions, p| {
-                    positions.insert(p);
-                    positions
-                });
-
-        for i in 0..GRID_WIDTH {
-            let mut line = String::new();
-            for j in 0..GRID_HEIGHT {
-                if positions.contains(&(i, j)) {
-                    line.push('*');
-                } else {
-                    line.push('.');
-                }
+            let positions: HashSet<(i64, i64)> = state.iter().map(|r| r.pos).collect();
+            if pairwise_manhattan(&positions) < 10_000_000 {
+                return Ok(iter as u32);
             }
-            println!("{}", line);
         }
 
-        Ok(0)
+        Err(AdventError::InputParseError(
+            "robots never cluster into a christmas tree".into(),
+        ))
     }
 }
 
+fn step_robots(robots: &[Robot]) -> Vec<Robot> {
+    robots
+        .iter()
+        .map(|r| {
+            let mut r = r.clone();
+            r.move_spaces(1, GRID_WIDTH, GRID_HEIGHT);
+            r
+        })
+        .collect()
+}
+
+/// Sum of Manhattan distances over all ordered pairs of positions. Robots bunch up to draw the
+/// tree, which makes this drop sharply.
+///
+/// Each axis is summed independently: after sorting, the `i`-th coordinate is larger than `i`
+/// others and smaller than `n - 1 - i` others.
+fn pairwise_manhattan(positions: &HashSet<(i64, i64)>) -> i64 {
+    let axis_sum = |mut values: Vec<i64>| {
+        values.sort_unstable();
+        let n = values.len() as i64;
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| v * (2 * i as i64 - (n - 1)))
+            .sum::<i64>()
+    };
+
+    let xs = positions.iter().map(|p| p.0).collect();
+    let ys = positions.iter().map(|p| p.1).collect();
+    2 * (axis_sum(xs) + axis_sum(ys))
+}
+
 fn parse_line(line: &str) -> Robot {
     let robot_regex = regex!("p=(\\d+),(\\d+) v=(-?\\d+),(-?\\d+)");
     let (_, [x, y, vx, vy]) = robot_regex
@@ -100,7 +107,7 @@ fn parse_line(line: &str) -> Robot {
 const GRID_WIDTH: i64 = 101;
 const GRID_HEIGHT: i64 = 103;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Robot {
     pos: (i64, i64),
     velocity: (i64, i64),
@@ -145,6 +152,7 @@ fn modulus(a: i64, b: i64) -> i64 {
 #[cfg(test)]
 mod test {
     use super::*;
+    use advent_common::cycle::simulate_until;
 
     #[test]
     fn parse_sample() {
@@ -197,4 +205,30 @@ mod test {
 
         let _ = lines.iter().map(|s| parse_line(s)).collect::<Vec<_>>();
     }
+
+    #[test]
+    fn pairwise_distance() {
+        let positions = HashSet::from([(0, 0), (3, 4), (1, 1)]);
+        let mut brute = 0;
+        for p1 in positions.iter() {
+            for p2 in positions.iter() {
+                brute += i64::abs(p1.0 - p2.0) + i64::abs(p1.1 - p2.1);
+            }
+        }
+        assert_eq!(brute, pairwise_manhattan(&positions));
+    }
+
+    #[test]
+    fn periodic_layout() {
+        let robots = vec![parse_line("p=0,4 v=3,-3"), parse_line("p=9,5 v=-3,-3")];
+        let period = brent(robots.clone(), |r: &Vec<Robot>| step_robots(r)).period;
+        assert_eq!((GRID_WIDTH * GRID_HEIGHT) as usize, period);
+
+        let n = 1_000_000_000_000;
+        let far = simulate_until(robots.clone(), |r: &Vec<Robot>| step_robots(r), n);
+        for (mut robot, moved) in robots.into_iter().zip(far) {
+            robot.move_spaces(n as i64, GRID_WIDTH, GRID_HEIGHT);
+            assert_eq!(robot, moved);
+        }
+    }
 }
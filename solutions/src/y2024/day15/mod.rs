@@ -1,4 +1,5 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::input::split_sections;
 use grid::{Direction, Grid, Space};
 
 mod grid;
@@ -8,8 +9,9 @@ pub struct Day15;
 impl AdventProblem for Day15 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let (mut grid, i) = parse_grid(&lines);
-        let directions = parse_directions(&lines, i + 1);
+        let [grid, directions] = split_sections(&lines)?;
+        let mut grid = parse_grid(grid.lines);
+        let directions = parse_directions(directions.lines);
 
         for dir in directions {
             grid.move_robot_1(dir);
@@ -31,8 +33,9 @@ impl AdventProblem for Day15 {
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let (mut grid, i) = parse_wide_grid(&lines);
-        let directions = parse_directions(&lines, i + 1);
+        let [grid, directions] = split_sections(&lines)?;
+        let mut grid = parse_wide_grid(grid.lines);
+        let directions = parse_directions(directions.lines);
 
         for dir in directions {
             grid.move_robot_2(dir);
@@ -54,18 +57,13 @@ impl AdventProblem for Day15 {
     }
 }
 
-fn parse_grid<S: AsRef<str>>(lines: &[S]) -> (Grid, usize) {
+fn parse_grid<S: AsRef<str>>(lines: &[S]) -> Grid {
     let mut spaces = Vec::new();
-    let (mut i, n) = (0, lines.len());
     let mut robot = (0, 0);
 
-    while i < n {
-        let line = lines[i].as_ref();
-        if line.is_empty() {
-            break;
-        }
+    for (i, line) in lines.iter().enumerate() {
         let mut row = Vec::new();
-        for (j, c) in line.chars().enumerate() {
+        for (j, c) in line.as_ref().chars().enumerate() {
             let space = Space::from(c);
             if space == Space::Robot {
                 robot = (i, j);
@@ -73,24 +71,18 @@ fn parse_grid<S: AsRef<str>>(lines: &[S]) -> (Grid, usize) {
             row.push(space);
         }
         spaces.push(row);
-        i += 1;
     }
 
-    (Grid { spaces, robot }, i)
+    Grid { spaces, robot }
 }
 
-fn parse_wide_grid<S: AsRef<str>>(lines: &[S]) -> (Grid, usize) {
+fn parse_wide_grid<S: AsRef<str>>(lines: &[S]) -> Grid {
     let mut spaces = Vec::new();
-    let (mut i, n) = (0, lines.len());
     let mut robot = (0, 0);
 
-    while i < n {
-        let line = lines[i].as_ref();
-        if line.is_empty() {
-            break;
-        }
+    for (i, line) in lines.iter().enumerate() {
         let mut row = Vec::new();
-        for (j, c) in line.chars().enumerate() {
+        for (j, c) in line.as_ref().chars().enumerate() {
             match Space::from(c) {
                 Space::Empty => {
                     row.push(Space::Empty);
@@ -113,25 +105,17 @@ fn parse_wide_grid<S: AsRef<str>>(lines: &[S]) -> (Grid, usize) {
             }
         }
         spaces.push(row);
-        i += 1;
     }
 
-    (Grid { spaces, robot }, i)
+    Grid { spaces, robot }
 }
 
-fn parse_directions(lines: &[String], start: usize) -> Vec<Direction> {
-    let mut i = start;
-    let mut directions = Vec::new();
-
-    while i < lines.len() {
-        let line = &lines[i];
-        for c in line.chars() {
-            directions.push(Direction::from(c));
-        }
-        i += 1;
-    }
-
-    directions
+fn parse_directions(lines: &[String]) -> Vec<Direction> {
+    lines
+        .iter()
+        .flat_map(|line| line.chars())
+        .map(Direction::from)
+        .collect()
 }
 
 #[cfg(test)]
@@ -144,10 +128,10 @@ mod test {
             "#######", "#...#.#", "#.....#", "#..OO@#", "#..O..#", "#.....#", "#######",
         ];
 
-        let (mut grid, _) = parse_wide_grid(&lines);
+        let mut grid = parse_wide_grid(&lines);
         grid.move_robot_2(Direction::Left);
 
-        let expected = vec![
+        let expected = [
             "##############",
             "##......##..##",
             "##..........##",
@@ -177,10 +161,10 @@ mod test {
             "#######", "#...#.#", "#.....#", "#..OO.#", "#..O..#", "#..@..#", "#######",
         ];
 
-        let (mut grid, _) = parse_wide_grid(&lines);
+        let mut grid = parse_wide_grid(&lines);
         grid.move_robot_2(Direction::Up);
 
-        let expected = vec![
+        let expected = [
             "##############",
             "##......##..##",
             "##....[]....##",
@@ -1,4 +1,5 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::input::{header, header_value, signed_ints, split_sections};
 use computer::Computer;
 
 mod computer;
@@ -8,7 +9,7 @@ pub struct Day17;
 impl AdventProblem for Day17 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut computer = parse_lines(&lines);
+        let mut computer = parse_lines(&lines)?;
         computer.run();
         println!("{:?}", computer.outputs().iter());
         Ok(0)
@@ -20,35 +21,25 @@ impl AdventProblem for Day17 {
     }
 }
 
-fn parse_lines(lines: &[String]) -> Computer {
-    let a = lines[0]
-        .split(": ")
-        .nth(1)
-        .unwrap()
-        .parse::<u64>()
-        .expect("numeric register value");
-
-    let b = lines[1]
-        .split(": ")
-        .nth(1)
-        .unwrap()
-        .parse::<u64>()
-        .expect("numeric register value");
-
-    let c = lines[2]
-        .split(": ")
-        .nth(1)
-        .unwrap()
-        .parse::<u64>()
-        .expect("numeric register value");
-
-    let instructions = lines[4]
-        .split(": ")
-        .nth(1)
-        .unwrap()
-        .split(",")
-        .map(|s| s.parse().expect("numeric opcode"))
-        .collect::<Vec<u8>>();
-
-    Computer::new(instructions, a, b, c)
+fn parse_lines(lines: &[String]) -> Result<Computer, AdventError> {
+    let [registers, program] = split_sections(lines)?;
+    if registers.len() != 3 || program.len() != 1 {
+        return Err(AdventError::InputParseError(
+            "expected three register lines followed by one program line".into(),
+        ));
+    }
+
+    let mut values = [0; 3];
+    for ((i, line), (name, value)) in registers
+        .iter()
+        .zip(["A", "B", "C"].iter().zip(&mut values))
+    {
+        *value = header_value(i, line, &format!("Register {name}"))?;
+    }
+    let [a, b, c] = values;
+
+    let (i, line) = program.iter().next().expect("one program line");
+    let instructions = signed_ints::<u8>(header(i, line, "Program")?).map_err(|e| e.at_line(i))?;
+
+    Ok(Computer::new(instructions, a, b, c))
 }
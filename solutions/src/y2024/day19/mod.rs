@@ -1,88 +1,65 @@
 use crate::{AdventError, AdventProblem};
-use std::collections::HashMap;
+use advent_common::input::split_sections;
+use advent_common::trie::Trie;
 
 pub struct Day19;
 
 impl AdventProblem for Day19 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let patterns = lines[0].split(", ").collect::<Vec<_>>();
-        let designs = lines.iter().skip(2).collect::<Vec<_>>();
-
-        let mut total = 0;
-        for d in designs {
-            if is_constructable(d, &patterns, 0) {
-                total += 1;
-            }
-        }
+        let (patterns, designs) = parse_lines(&lines)?;
+
+        let total = designs
+            .iter()
+            .filter(|d| arrangements(d, &patterns).is_none_or(|ways| ways > 0))
+            .count();
 
-        Ok(total as u32)
+        Ok(total as u64)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let patterns = lines[0].split(", ").collect::<Vec<_>>();
-        let designs = lines.iter().skip(2).collect::<Vec<_>>();
-
-        let mut total = 0;
-        let mut sequence_counts: HashMap<&str, u64> = HashMap::new();
-        for d in designs {
-            total += count_constructable(d, &patterns, &mut sequence_counts, 0);
-        }
-        println!("{}", total);
-        Ok(0)
+        let (patterns, designs) = parse_lines(&lines)?;
+
+        designs
+            .iter()
+            .try_fold(0u64, |total, d| {
+                arrangements(d, &patterns).and_then(|ways| total.checked_add(ways))
+            })
+            .ok_or_else(|| {
+                AdventError::InputParseError("arrangement count does not fit in a u64".into())
+            })
     }
 }
 
-fn is_constructable(line: &str, patterns: &[&str], i: usize) -> bool {
-    let n = line.len();
-    if i == n {
-        return true;
-    }
+fn parse_lines(lines: &[String]) -> Result<(Trie, &[String]), AdventError> {
+    let [patterns, designs] = split_sections(lines)?;
+    let patterns = patterns
+        .lines
+        .iter()
+        .flat_map(|line| line.split(", "))
+        .collect();
 
-    for &p in patterns {
-        if i + p.len() <= n
-            && &line[i..i + p.len()] == p
-            && is_constructable(line, patterns, i + p.len())
-        {
-            return true;
-        }
-    }
-    false
+    Ok((patterns, designs.lines))
 }
 
-fn count_constructable<'a>(
-    line: &'a str,
-    patterns: &[&str],
-    sequence_counts: &mut HashMap<&'a str, u64>,
-    i: usize,
-) -> u64 {
-    let n = line.len();
-
-    if sequence_counts.contains_key(&line[i..]) {
-        return *sequence_counts.get(&line[i..]).unwrap();
+/// Number of ways to build `design` out of towel patterns, or `None` if it does not fit in a
+/// `u64`.
+///
+/// `ways[i]` counts the arrangements of the suffix starting at `i`, so each position only looks
+/// at the patterns that actually start there. An overflowing count stays `None` for every
+/// position that builds on it.
+fn arrangements(design: &str, patterns: &Trie) -> Option<u64> {
+    let n = design.len();
+    let mut ways = vec![Some(0u64); n + 1];
+    ways[n] = Some(1);
+
+    for i in (0..n).rev() {
+        ways[i] = patterns
+            .prefixes_at(design, i)
+            .try_fold(0u64, |sum, len| sum.checked_add(ways[i + len]?));
     }
 
-    if i == n {
-        return 1;
-    }
-
-    let mut total = 0;
-    for &p in patterns {
-        if i + p.len() <= n && &line[i..i + p.len()] == p {
-            match sequence_counts.get(&line[i + p.len()..]) {
-                Some(cnt) => {
-                    total += *cnt;
-                }
-                None => {
-                    let cnt = count_constructable(line, patterns, sequence_counts, i + p.len());
-                    total += cnt;
-                }
-            }
-        }
-    }
-    *sequence_counts.entry(&line[i..]).or_default() += total;
-
-    total
+    ways[0]
 }
 
 #[cfg(test)]
@@ -91,55 +68,29 @@ mod test {
 
     #[test]
     fn sample() {
-        let patterns = vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
-
-        assert!(is_constructable("brwrr", &patterns, 0));
-        assert!(is_constructable("bggr", &patterns, 0));
-        assert!(is_constructable("gbbr", &patterns, 0));
-        assert!(is_constructable("rrbgbr", &patterns, 0));
-        assert!(!is_constructable("ubwu", &patterns, 0));
-        assert!(is_constructable("bwurrg", &patterns, 0));
-        assert!(is_constructable("brgr", &patterns, 0));
-        assert!(!is_constructable("bbrgwb", &patterns, 0));
-    }
-
-    #[test]
-    fn sample_2() {
-        let patterns = vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
-
-        let mut sequence_counts = HashMap::new();
+        let patterns = Trie::from_iter(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+
+        let designs = [
+            ("brwrr", 2),
+            ("bggr", 1),
+            ("gbbr", 4),
+            ("rrbgbr", 6),
+            ("ubwu", 0),
+            ("bwurrg", 1),
+            ("brgr", 2),
+            ("bbrgwb", 0),
+        ];
+
+        for (design, expected) in designs {
+            assert_eq!(Some(expected), arrangements(design, &patterns), "{design}");
+        }
 
+        // Fibonacci many ways, which pass u64::MAX after 92 stripes.
+        let stripes = Trie::from_iter(["w", "ww"]);
         assert_eq!(
-            2,
-            count_constructable("brwrr", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            1,
-            count_constructable("bggr", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            4,
-            count_constructable("gbbr", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            6,
-            count_constructable("rrbgbr", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            0,
-            count_constructable("ubwu", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            1,
-            count_constructable("bwurrg", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            2,
-            count_constructable("brgr", &patterns, &mut sequence_counts, 0)
-        );
-        assert_eq!(
-            0,
-            count_constructable("bbrgwb", &patterns, &mut sequence_counts, 0)
+            Some(7_540_113_804_746_346_429),
+            arrangements(&"w".repeat(91), &stripes)
         );
+        assert_eq!(None, arrangements(&"w".repeat(93), &stripes));
     }
 }
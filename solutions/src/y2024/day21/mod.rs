@@ -1,29 +1,32 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::memo::Memo;
 use std::collections::HashMap;
 
 pub struct Day21;
 
 impl AdventProblem for Day21 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let mut total = 0;
         for line in &lines {
             let dirs = robot_dirs(line);
 
-            let num = line.replace("A", "").parse::<u32>().expect("numeric value");
-            total += num * (dirs.len() as u32);
+            let num = line.replace("A", "").parse::<u64>().expect("numeric value");
+            total += num * (dirs.len() as u64);
         }
 
         Ok(total)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
+        let mut memo = Memo::labelled("2024 day21 controller presses");
+
         let mut total = 0;
         for line in &lines {
-            let dirs = cached_robot_dirs(line);
+            let presses = cached_robot_presses(line, 25, &mut memo);
 
-            let num = line.replace("A", "").parse::<u32>().expect("numeric value");
-            total += num * (dirs.len() as u32);
+            let num = line.replace("A", "").parse::<u64>().expect("numeric value");
+            total += num * presses;
         }
 
         Ok(total)
@@ -43,19 +46,46 @@ fn robot_dirs(seq: &str) -> String {
     robot_dirs
 }
 
-fn cached_robot_dirs(seq: &str) -> String {
+/// Number of presses on the outermost keypad needed to type `seq` on the numeric keypad through
+/// `robots` directional keypads. Only the length is tracked, so deep robot chains stay cheap.
+fn cached_robot_presses(
+    seq: &str,
+    robots: usize,
+    memo: &mut Memo<(char, char, usize), u64>,
+) -> u64 {
     let keypad = keypad();
     let controller = controls();
 
-    let mut dirs = directions(seq, &keypad, (0, 0));
+    let dirs = directions(seq, &keypad, (0, 0));
+    controller_presses(&dirs, robots, &controller, memo)
+}
 
-    let mut cache: HashMap<(char, char), String> = HashMap::new();
+/// Presses needed to type `seq` on a directional keypad that is itself driven through `depth`
+/// more directional keypads. Every key press starts from the previous key, so the cost of each
+/// `(from, to)` move at a given depth is memoised.
+fn controller_presses(
+    seq: &str,
+    depth: usize,
+    controller: &HashMap<char, (usize, usize)>,
+    memo: &mut Memo<(char, char, usize), u64>,
+) -> u64 {
+    if depth == 0 {
+        return seq.len() as u64;
+    }
 
-    for _ in 0..26 {
-        dirs = directions_with_cache(&dirs, &controller, (1, 0), &mut cache);
+    let mut prev = 'A';
+    let mut total = 0;
+    for next in seq.chars() {
+        let from = prev;
+        total += memo.get_or_insert_with((from, next, depth), |memo| {
+            let mut moves = append_directions(controller[&from], controller[&next], (1, 0));
+            moves.push('A');
+            controller_presses(&moves, depth - 1, controller, memo)
+        });
+        prev = next;
     }
-    println!("robot_dirs={}", dirs);
-    dirs
+
+    total
 }
 
 fn push_horizontal(s: &mut String, start: (usize, usize), end: (usize, usize)) {
@@ -97,37 +127,6 @@ fn directions(seq: &str, keys: &HashMap<char, (usize, usize)>, empty: (usize, us
     directions
 }
 
-fn directions_with_cache(
-    seq: &str,
-    keys: &HashMap<char, (usize, usize)>,
-    empty: (usize, usize),
-    cache: &mut HashMap<(char, char), String>,
-) -> String {
-    let mut curr_ch = 'A';
-    let mut pos = keys[&'A'];
-    let mut directions = String::new();
-
-    for next_ch in seq.chars() {
-        let next = keys[&next_ch];
-
-        match cache.get(&(curr_ch, next_ch)) {
-            Some(s) => {
-                directions.push_str(s);
-            }
-            None => {
-                let dirs = append_directions(pos, next, empty);
-                directions.push_str(&dirs);
-                cache.insert((curr_ch, next_ch), dirs);
-            }
-        }
-        directions.push('A');
-        pos = next;
-        curr_ch = next_ch;
-    }
-
-    directions
-}
-
 fn append_directions(pos: (usize, usize), next: (usize, usize), empty: (usize, usize)) -> String {
     let mut directions = String::new();
     if pos.1 > next.1 {
@@ -320,4 +319,15 @@ mod test {
         assert_eq!(64, robot_dirs("456A").len());
         assert_eq!(64, robot_dirs("379A").len());
     }
+
+    #[test]
+    fn cached_matches_expanded() {
+        let mut memo = Memo::new();
+        for code in ["029A", "980A", "179A", "456A", "379A"] {
+            assert_eq!(
+                robot_dirs(code).len() as u64,
+                cached_robot_presses(code, 2, &mut memo)
+            );
+        }
+    }
 }
@@ -1,88 +1,150 @@
 use crate::{AdventError, AdventProblem};
-use std::collections::HashMap;
+use advent_common::gf2::{Gf2Matrix, Gf2Powers, Gf2Vector};
+use std::thread;
 
 pub struct Day22;
 
 impl AdventProblem for Day22 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let total = lines
-            .iter()
-            .map(|s| {
-                let n = s.parse::<u64>().expect("numeric value");
-                final_secret_number(n)
-            })
-            .sum::<u64>();
-
-        println!("total={}", total);
-        Ok(0)
+        let secrets = parse_secrets(&lines)?;
+        let jump = SecretJump::new(SECRETS_PER_DAY as u64);
+        Ok(secrets
+            .into_iter()
+            .map(|s| jump.advance(s, SECRETS_PER_DAY as u64))
+            .sum())
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut prices: HashMap<(i64, i64, i64, i64), u64> = HashMap::new();
-
-        for line in &lines {
-            let n = line.parse::<u64>().expect("numeric value");
-            let next_prices = banana_prices(n);
-
-            for (seq, p) in next_prices {
-                *prices.entry(seq).or_insert(0) += p;
-            }
-        }
-
-        println!(
-            "best_price={}",
-            prices.values().max().expect("empty sequence")
-        );
-
-        Ok(0)
+        let secrets = parse_secrets(&lines)?;
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Ok(most_bananas(&secrets, threads))
     }
 }
 
-fn banana_prices(n: u64) -> HashMap<(i64, i64, i64, i64), u64> {
-    let first = n;
-    let second = next_secret_number(first);
-    let third = next_secret_number(second);
-    let fourth = next_secret_number(third);
+fn parse_secrets(lines: &[String]) -> Result<Vec<u64>, AdventError> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .map_err(|e| AdventError::from(e).at_line(i))
+        })
+        .collect()
+}
 
-    let mut prices: HashMap<(i64, i64, i64, i64), u64> = HashMap::new();
+/// Price changes lie in `-9..=9`, so a window of four of them is a base-19 number.
+const CHANGE_VALUES: usize = 19;
+const WINDOWS: usize = CHANGE_VALUES.pow(4);
 
-    let mut prev_seq = (
-        compute_diff(first, second),
-        compute_diff(second, third),
-        compute_diff(third, fourth),
-    );
+/// Number of new secrets each buyer generates in a day.
+const SECRETS_PER_DAY: usize = 2000;
 
-    let mut prev = fourth;
+/// Banana totals for every window of four price changes, over the buyers added so far.
+struct Market {
+    totals: Vec<u64>,
+    /// `seen[w]` is the stamp of the last buyer whose prices contained window `w`, so only the
+    /// first sale per buyer counts without clearing anything between buyers.
+    seen: Vec<u32>,
+    buyers: u32,
+}
 
-    for _ in 3..2000 {
-        let next = next_secret_number(prev);
-        let price = next % 10;
-        let diff = compute_diff(prev, next);
+impl Market {
+    fn new() -> Self {
+        Self {
+            totals: vec![0; WINDOWS],
+            seen: vec![0; WINDOWS],
+            buyers: 0,
+        }
+    }
 
-        let seq = (prev_seq.0, prev_seq.1, prev_seq.2, diff);
+    /// Sells to the buyer starting from `secret` at the first occurrence of each window.
+    fn add_buyer(&mut self, secret: u64) {
+        self.buyers += 1;
+        let stamp = self.buyers;
+
+        let mut secret = secret;
+        let mut price = secret % 10;
+        let mut window = 0;
+        for i in 0..SECRETS_PER_DAY {
+            secret = next_secret_number(secret);
+            let next = secret % 10;
+            let change = (next + 9 - price) as usize;
+            window = (window * CHANGE_VALUES + change) % WINDOWS;
+            price = next;
+
+            if i >= 3 && self.seen[window] != stamp {
+                self.seen[window] = stamp;
+                self.totals[window] += price;
+            }
+        }
+    }
 
-        if price > 0 {
-            prices.entry(seq).or_insert(price);
+    fn merge(mut self, other: Market) -> Self {
+        for (total, other) in self.totals.iter_mut().zip(other.totals) {
+            *total += other;
         }
+        self
+    }
 
-        prev = next;
-        prev_seq = (prev_seq.1, prev_seq.2, diff);
+    fn best(&self) -> u64 {
+        self.totals.iter().copied().max().unwrap_or(0)
     }
+}
 
-    prices
+/// Most bananas a single window of four price changes can buy across all buyers, with the
+/// buyers split over up to `threads` threads.
+fn most_bananas(secrets: &[u64], threads: usize) -> u64 {
+    let chunk = secrets.len().div_ceil(threads.max(1)).max(1);
+    let market = thread::scope(|scope| {
+        let workers = secrets
+            .chunks(chunk)
+            .map(|buyers| {
+                scope.spawn(move || {
+                    let mut market = Market::new();
+                    for &secret in buyers {
+                        market.add_buyer(secret);
+                    }
+                    market
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("market thread panicked"))
+            .fold(Market::new(), Market::merge)
+    });
+    market.best()
 }
 
-fn compute_diff(first: u64, second: u64) -> i64 {
-    (second % 10) as i64 - (first % 10) as i64
+/// Secrets have 24 bits.
+const SECRET_BITS: usize = 24;
+
+/// Advances secrets many steps at once.
+///
+/// Every stage of [`next_secret_number`] is a shift, an XOR or a mask to 24 bits, so a step is
+/// a linear map over GF(2) on the secret's bits. `n` steps are its `n`-th matrix power, applied
+/// in O(log n) matrix-vector products.
+struct SecretJump {
+    powers: Gf2Powers,
 }
 
-fn final_secret_number(n: u64) -> u64 {
-    let mut next = n;
-    for _ in 0..2000 {
-        next = next_secret_number(next);
+impl SecretJump {
+    /// Jump-ahead for up to `max_steps` steps.
+    fn new(max_steps: u64) -> Self {
+        let step = Gf2Matrix::from_linear_fn(SECRET_BITS, next_secret_number);
+        Self {
+            powers: Gf2Powers::new(step, max_steps),
+        }
+    }
+
+    /// The secret `steps` steps after `secret`.
+    fn advance(&self, secret: u64, steps: u64) -> u64 {
+        let bits = Gf2Vector::from_bits(secret, SECRET_BITS);
+        self.powers.apply(&bits, steps).to_bits()
     }
-    next
 }
 
 fn next_secret_number(n: u64) -> u64 {
@@ -99,6 +161,15 @@ fn mix_and_prune(x: u64, y: u64) -> u64 {
 mod test {
     use super::*;
 
+    /// The iterative reference for [`SecretJump`].
+    fn final_secret_number(n: u64) -> u64 {
+        let mut next = n;
+        for _ in 0..SECRETS_PER_DAY {
+            next = next_secret_number(next);
+        }
+        next
+    }
+
     #[test]
     fn sample() {
         let seq = vec![
@@ -113,6 +184,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn jump_matches_stepping() {
+        let jump = SecretJump::new(1 << 12);
+        for secret in [0, 1, 10, 100, 2024, 16_777_215] {
+            assert_eq!(final_secret_number(secret), jump.advance(secret, 2000));
+            let mut stepped = secret;
+            for steps in 0..50 {
+                assert_eq!(stepped, jump.advance(secret, steps));
+                stepped = next_secret_number(stepped);
+            }
+        }
+        let lines = ["1", "10", "100", "2024"].map(String::from).to_vec();
+        assert_eq!(37327623, Day22.run_part_1(lines).unwrap());
+    }
+
+    #[test]
+    fn sample_part_2() {
+        let secrets = [1, 2, 3, 2024];
+        assert_eq!(23, most_bananas(&secrets, 1));
+        assert_eq!(23, most_bananas(&secrets, 3));
+        assert_eq!(23, most_bananas(&secrets, 16));
+    }
+
     #[test]
     fn test_final_secret_number() {
         assert_eq!(8685429, final_secret_number(1));
@@ -1,74 +1,79 @@
-use crate::{AdventError, AdventProblem};
-use std::collections::{HashMap, HashSet};
+use crate::{AdventError, AdventProblem, PuzzleAnswer};
+use advent_common::graph::Graph;
 
 pub struct Day23;
 
 impl AdventProblem for Day23 {
-    type Answer = u32;
-    fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let neighbors = build_neighbors(&lines);
-        let mut triplets = HashSet::new();
+    type Answer = PuzzleAnswer;
 
-        for &n1 in neighbors.keys() {
-            for &n2 in &neighbors[&n1] {
-                for &n3 in &neighbors[&n2] {
-                    for &n4 in &neighbors[&n3] {
-                        if n4 == n1 {
-                            let mut triplet = [n1, n2, n3];
-                            triplet.sort();
-                            triplets.insert((triplet[0], triplet[1], triplet[2]));
-                        }
-                    }
-                }
-            }
-        }
+    fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
+        let network = build_network(&lines)?;
 
-        let mut total = 0;
-        for (n1, n2, n3) in triplets {
-            if n1.starts_with("t") || n2.starts_with("t") || n3.starts_with("t") {
+        let mut total = 0u32;
+        network.for_each_k_clique(3, |triplet| {
+            if network.names(triplet).any(|n| n.starts_with('t')) {
                 total += 1;
             }
-        }
+        });
 
-        Ok(total)
+        Ok(total.into())
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let adjacency = build_neighbors(&lines);
-
-        let mut biggest = Vec::new();
+        let network = build_network(&lines)?;
 
-        for (&node, neighbors) in &adjacency {
-            let mut group = vec![node];
-            for &nei in neighbors {
-                if group.iter().all(|&n| adjacency[n].contains(nei)) {
-                    group.push(nei);
-                }
-            }
+        Ok(lan_party_password(&network).into())
+    }
+}
 
-            if group.len() > biggest.len() {
-                biggest = group;
-            }
-        }
+/// Names of the largest set of fully interconnected computers, sorted and comma-joined.
+fn lan_party_password(network: &Graph<&str>) -> String {
+    let mut biggest = network
+        .names(&network.max_clique())
+        .copied()
+        .collect::<Vec<_>>();
+    biggest.sort_unstable();
 
-        biggest.sort();
+    biggest.join(",")
+}
 
-        let password = biggest.join(",");
+fn build_network(lines: &[String]) -> Result<Graph<&str>, AdventError> {
+    let mut network = Graph::undirected();
+    for line in lines {
+        let (first, second) = line.split_once('-').ok_or_else(|| {
+            AdventError::InputParseError(format!("invalid connection {line}").into())
+        })?;
 
-        println!("password={}", password);
-        Ok(0)
+        network.add_edge(first, second);
     }
+    Ok(network)
 }
 
-fn build_neighbors(lines: &Vec<String>) -> HashMap<&str, HashSet<&str>> {
-    let mut neighbors: HashMap<&str, HashSet<&str>> = HashMap::new();
-    for line in lines {
-        let mut conn = line.split("-");
-        let first = conn.next().unwrap();
-        let second = conn.next().unwrap();
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Vec<String> {
+        [
+            "kh-tc", "qp-kh", "de-cg", "ka-co", "yn-aq", "qp-ub", "cg-tb", "vc-aq", "tb-ka",
+            "wh-tc", "yn-cg", "kh-ub", "ta-co", "de-co", "tc-td", "tb-wq", "wh-td", "ta-ka",
+            "td-qp", "aq-cg", "wq-ub", "ub-vc", "de-ta", "wq-aq", "wq-vc", "wh-yn", "ka-de",
+            "kh-ta", "co-tc", "wh-qp", "tb-vc", "td-yn",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn sample_part_1() {
+        assert_eq!(PuzzleAnswer::U32(7), Day23.run_part_1(sample()).unwrap());
+    }
 
-        neighbors.entry(first).or_default().insert(second);
-        neighbors.entry(second).or_default().insert(first);
+    #[test]
+    fn sample_part_2() {
+        let lines = sample();
+        let network = build_network(&lines).unwrap();
+        assert_eq!("co,de,ka,ta", lan_party_password(&network));
     }
-    neighbors
 }
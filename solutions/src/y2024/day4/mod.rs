@@ -1,4 +1,5 @@
-use std::collections::{HashSet, VecDeque};
+use advent_common::floodfill::construct_grid;
+use advent_common::rotation::{diagonals, transpose, Rotation};
 
 use crate::{AdventError, AdventProblem};
 
@@ -6,201 +7,65 @@ pub struct Day4;
 
 const XMAS: [char; 4] = ['X', 'M', 'A', 'S'];
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-    NorthWest,
-    NorthEast,
-    SouthWest,
-    SouthEast,
-}
-
-impl Direction {
-    fn offset(self) -> (i32, i32) {
-        match self {
-            Self::North => (0, 1),
-            Self::South => (0, -1),
-            Self::East => (1, 0),
-            Self::West => (-1, 0),
-            Self::NorthWest => (-1, -1),
-            Self::NorthEast => (-1, 1),
-            Self::SouthWest => (1, -1),
-            Self::SouthEast => (1, 1),
-        }
-    }
-
-    fn transition(self, row: usize, col: usize) -> (i32, i32) {
-        let (ro, co) = self.offset();
-        (row as i32 + ro, col as i32 + co)
-    }
-}
-
 impl AdventProblem for Day4 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
-
-        Ok(count_xmas(grid))
+        Ok(count_xmas(construct_grid(&lines)))
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
-
-        Ok(count_cross_mas(grid))
+        Ok(count_cross_mas(construct_grid(&lines)))
     }
 }
 
+/// Counts XMAS in every direction by scanning, forwards and backwards, the rows of the grid,
+/// of its transpose, and the diagonals of the grid and of the grid turned a quarter.
 fn count_xmas(grid: Vec<Vec<char>>) -> u32 {
-    let (m, n) = (grid.len(), grid[0].len());
-    let mut queue = VecDeque::new();
-    let mut count = 0;
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == 'X' {
-                queue.push_back((i, j, Direction::North, 1));
-                queue.push_back((i, j, Direction::South, 1));
-                queue.push_back((i, j, Direction::East, 1));
-                queue.push_back((i, j, Direction::West, 1));
-                queue.push_back((i, j, Direction::NorthWest, 1));
-                queue.push_back((i, j, Direction::NorthEast, 1));
-                queue.push_back((i, j, Direction::SouthWest, 1));
-                queue.push_back((i, j, Direction::SouthEast, 1));
-            }
-        }
-    }
-
-    while !queue.is_empty() {
-        let (r, c, direction, offset) = queue.pop_front().unwrap();
-        if offset == 4 {
-            count += 1;
-            continue;
-        }
-
-        let (nr, nc) = direction.transition(r, c);
-        if nr < 0 || nr >= m as i32 || nc < 0 || nc >= n as i32 {
-            continue;
-        }
-        let (nr, nc) = (nr as usize, nc as usize);
-        if grid[nr][nc] == XMAS[offset] {
-            queue.push_back((nr, nc, direction, offset + 1));
-        }
-    }
-    count
+    let turned = Rotation::Clockwise.rotate(&grid);
+    let lines = [
+        grid.clone(),
+        transpose(&grid),
+        diagonals(&grid),
+        diagonals(&turned),
+    ]
+    .concat();
+
+    let reversed = XMAS.iter().rev().copied().collect::<Vec<_>>();
+    lines
+        .iter()
+        .flat_map(|line| line.windows(XMAS.len()))
+        .filter(|&w| w == XMAS || w == reversed)
+        .count() as u32
 }
 
 /**
- * M
- *  A
- *   S
+ * M . S
+ * . A .
+ * M . S
  *
+ * Matched in each of the grid's four orientations, which covers every way the two MAS can
+ * cross.
  */
 fn count_cross_mas(grid: Vec<Vec<char>>) -> u32 {
-    let end_points = find_mas(grid);
-    let mut pairs = HashSet::new();
-
-    for &(r, c, direction) in end_points.iter() {
-        match direction {
-            /*
-             *   S   *
-             *     A
-             *   *   M
-             */
-            Direction::NorthWest => {
-                if end_points.contains(&(r, c + 2, Direction::NorthEast)) {
-                    pairs.insert((r + 1, c + 1));
-                }
-                if end_points.contains(&(r + 2, c, Direction::SouthWest)) {
-                    pairs.insert((r + 1, c + 1));
-                }
-            }
-            /*
-             *   *   S
-             *     A
-             *   M   *
-             */
-            Direction::NorthEast => {
-                if end_points.contains(&(r + 2, c, Direction::SouthEast)) {
-                    pairs.insert((r + 1, c - 1));
-                }
-                if c >= 2 && end_points.contains(&(r, c - 2, Direction::NorthWest)) {
-                    pairs.insert((r + 1, c - 1));
-                }
-            }
-            /*
-             *   M   *
-             *     A
-             *   *   S
-             */
-            Direction::SouthEast => {
-                if c >= 2 && end_points.contains(&(r, c - 2, Direction::SouthWest)) {
-                    pairs.insert((r - 1, c - 1));
-                }
-                if r >= 2 && end_points.contains(&(r - 2, c, Direction::NorthEast)) {
-                    pairs.insert((r - 1, c - 1));
-                }
-            }
-            /*
-             *   *   M
-             *     A
-             *   S   *
-             */
-            Direction::SouthWest => {
-                if end_points.contains(&(r, c + 2, Direction::SouthEast)) {
-                    pairs.insert((r - 1, c + 1));
-                }
-                if r >= 2 && end_points.contains(&(r - 2, c, Direction::NorthWest)) {
-                    pairs.insert((r - 1, c + 1));
+    let mut count = 0;
+    let mut grid = grid;
+    for _ in 0..4 {
+        for rows in grid.windows(3) {
+            for c in 0..rows[0].len().saturating_sub(2) {
+                let at = |dr: usize, dc: usize| rows[dr][c + dc];
+                if at(1, 1) == 'A'
+                    && at(0, 0) == 'M'
+                    && at(2, 0) == 'M'
+                    && at(0, 2) == 'S'
+                    && at(2, 2) == 'S'
+                {
+                    count += 1;
                 }
             }
-            _ => println!("unknown direction found"),
         }
+        grid = Rotation::Clockwise.rotate(&grid);
     }
-
-    pairs.len() as u32
-}
-
-fn find_mas(grid: Vec<Vec<char>>) -> HashSet<(usize, usize, Direction)> {
-    let (m, n) = (grid.len(), grid[0].len());
-    let mut queue = VecDeque::new();
-    let mut end_points = HashSet::new();
-
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == 'M' {
-                queue.push_back((i, j, Direction::NorthWest, 2));
-                queue.push_back((i, j, Direction::NorthEast, 2));
-                queue.push_back((i, j, Direction::SouthWest, 2));
-                queue.push_back((i, j, Direction::SouthEast, 2));
-            }
-        }
-    }
-
-    while !queue.is_empty() {
-        let (r, c, direction, offset) = queue.pop_front().unwrap();
-        if offset == 4 {
-            end_points.insert((r, c, direction));
-            continue;
-        }
-
-        let (nr, nc) = direction.transition(r, c);
-        if nr < 0 || nr >= m as i32 || nc < 0 || nc >= n as i32 {
-            continue;
-        }
-        let (nr, nc) = (nr as usize, nc as usize);
-        if grid[nr][nc] == XMAS[offset] {
-            queue.push_back((nr, nc, direction, offset + 1));
-        }
-    }
-    end_points
+    count
 }
 
 #[cfg(test)]
@@ -1,5 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
+use advent_common::graph::Graph;
+use advent_common::input::{signed_ints, signed_ints_n, split_sections, Section};
+
 use crate::{AdventError, AdventProblem};
 
 pub struct Day5;
@@ -7,8 +10,9 @@ pub struct Day5;
 impl AdventProblem for Day5 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let (rules, i) = parse_ordering_rules(&lines);
-        let requests = parse_print_requests(&lines, i + 1);
+        let [rules, requests] = split_sections(&lines)?;
+        let rules = parse_ordering_rules(&rules)?;
+        let requests = parse_print_requests(&requests)?;
 
         let total = requests
             .iter()
@@ -20,8 +24,9 @@ impl AdventProblem for Day5 {
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let (rules, i) = parse_ordering_rules(&lines);
-        let mut requests = parse_print_requests(&lines, i + 1);
+        let [rules, requests] = split_sections(&lines)?;
+        let rules = parse_ordering_rules(&rules)?;
+        let mut requests = parse_print_requests(&requests)?;
 
         let total = requests
             .iter_mut()
@@ -62,88 +67,63 @@ impl Request {
         true
     }
 
-    // 1. Retrieve all dependencies for each page, this should form a directed-acyclic graph
-    // 2. Traverse the graph in topological order removing each completed dependency
-    // 3. The order of the traversal will be the new order of the pages
+    // 1. Retrieve all dependencies between the pages of this request, this should form a
+    //    directed-acyclic graph
+    // 2. The topological order of that graph is the new order of the pages
+    //
+    // Returns false, leaving the pages untouched, if the rules for this request form a cycle.
     fn fix(&mut self, rules: &HashMap<u32, HashSet<u32>>) -> bool {
-        let n = self.pages.len();
-        let mut i = 0;
-        while i < n {
-            let page = self.pages[i];
-            let mut redo = false;
-            if let Some(dependencies) = rules.get(&page) {
-                for j in (i + 1)..n {
-                    if dependencies.contains(&self.pages[j]) {
-                        self.pages.swap(i, j);
-                        redo = true;
-                        break;
-                    }
+        let mut graph = Graph::directed();
+        let ids = self
+            .pages
+            .iter()
+            .map(|&page| graph.add_node(page))
+            .collect::<Vec<_>>();
+
+        for (i, page) in self.pages.iter().enumerate() {
+            let Some(dependencies) = rules.get(page) else {
+                continue;
+            };
+            for (j, dep) in self.pages.iter().enumerate() {
+                if i != j && dependencies.contains(dep) {
+                    graph.add_edge_ids(ids[j], ids[i]);
                 }
             }
-            if !redo {
-                i += 1;
+        }
+
+        match graph.toposort() {
+            Ok(order) => {
+                self.pages = graph.names(&order).copied().collect();
+                true
             }
+            Err(_) => false,
         }
-        true
     }
 }
 
 /**
  * Given ordering rule 47|53, map 53 -> 47 indicating that it must be printed after
  */
-fn parse_ordering_rules(lines: &[String]) -> (HashMap<u32, HashSet<u32>>, usize) {
-    let mut i = 0;
+fn parse_ordering_rules(
+    section: &Section<'_, String>,
+) -> Result<HashMap<u32, HashSet<u32>>, AdventError> {
     let mut rules: HashMap<u32, HashSet<u32>> = HashMap::new();
-
-    while i < lines.len() {
-        let line = &lines[i];
-        if line.is_empty() {
-            break;
-        }
-
-        let mut tokens = line.split("|");
-        let first = tokens
-            .next()
-            .expect("print rule first page")
-            .parse::<u32>()
-            .expect("non-numeric page in print rule");
-
-        let second = tokens
-            .next()
-            .expect("print rule second page")
-            .parse::<u32>()
-            .expect("non-numeric page in print rule");
-
+    for [first, second] in section.parse_lines(signed_ints_n::<u32, 2>)? {
         rules.entry(second).or_default().insert(first);
-        i += 1;
     }
 
-    (rules, i)
+    Ok(rules)
 }
 
 /**
- * Given ordering rule 47|53, map 53 -> 47 indicating that it must be printed after
+ * Each request is a comma-separated list of pages
  */
-fn parse_print_requests(lines: &[String], start: usize) -> Vec<Request> {
-    let mut i = start;
-    let mut requests = Vec::new();
-
-    while i < lines.len() {
-        let line = &lines[i];
-        if line.is_empty() {
-            break;
-        }
-
-        let pages = line
-            .split(",")
-            .map(|s| s.parse::<u32>().expect("non-numeric page in request"))
-            .collect::<Vec<_>>();
-
-        requests.push(Request { pages });
-        i += 1;
-    }
-
-    requests
+fn parse_print_requests(section: &Section<'_, String>) -> Result<Vec<Request>, AdventError> {
+    section.parse_lines(|line| {
+        Ok(Request {
+            pages: signed_ints(line)?,
+        })
+    })
 }
 
 #[cfg(test)]
@@ -198,7 +178,7 @@ mod test {
             (75, HashSet::from_iter([97])),
         ]);
 
-        request.fix(&rules);
+        assert!(request.fix(&rules));
         assert_eq!(vec![97, 75, 47, 61, 53], request.pages);
     }
 }
@@ -1,59 +1,37 @@
 use crate::{AdventError, AdventProblem};
-use std::collections::HashSet;
+use advent_common::bitgrid::BitGrid;
+use advent_common::cycle::find_cycle;
+use advent_common::direction::CardinalDirection;
+use advent_common::rotation::Rotation;
 
 pub struct Day6;
 
 impl AdventProblem for Day6 {
     type Answer = u32;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
+        let walls = BitGrid::from_lines(&lines, |c| c == '#')?;
 
-        let start = find_start(&grid);
-        let visited = find_visited(&grid, start);
-        Ok(visited.len() as u32)
+        let start = find_start(&lines);
+        let visited = find_visited(&walls, start);
+        Ok(visited.count_ones() as u32)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
+        let mut walls = BitGrid::from_lines(&lines, |c| c == '#')?;
 
-        let start = find_start(&grid);
-        let mut visited = find_visited(&grid, start);
-        visited.remove(&start);
+        let start = find_start(&lines);
+        let mut visited = find_visited(&walls, start);
+        visited.clear(start.0, start.1);
 
-        let obstacles = find_obstacles(&mut grid, start, &visited);
-        Ok(obstacles.len() as u32)
+        let obstacles = find_obstacles(&mut walls, start, &visited);
+        Ok(obstacles.count_ones() as u32)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    fn rotate(&self) -> Self {
-        match *self {
-            Self::North => Self::East,
-            Self::East => Self::South,
-            Self::South => Self::West,
-            Self::West => Self::North,
-        }
-    }
-}
-
-fn find_start(grid: &[Vec<char>]) -> (usize, usize) {
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == '^' {
+fn find_start(lines: &[String]) -> (usize, usize) {
+    for (i, row) in lines.iter().enumerate() {
+        for (j, c) in row.chars().enumerate() {
+            if c == '^' {
                 return (i, j);
             }
         }
@@ -61,78 +39,66 @@ fn find_start(grid: &[Vec<char>]) -> (usize, usize) {
     (0, 0)
 }
 
-fn find_visited(grid: &[Vec<char>], start: (usize, usize)) -> HashSet<(usize, usize)> {
-    let mut visited = HashSet::new();
-    let (mut curr, mut curr_dir) = (start, Direction::North);
-    visited.insert(curr);
+fn find_visited(walls: &BitGrid, start: (usize, usize)) -> BitGrid {
+    let mut visited = BitGrid::new(walls.rows(), walls.cols());
+    let (mut curr, mut curr_dir) = (start, CardinalDirection::North);
+    visited.set(curr.0, curr.1);
 
-    while let Some((row, col, direction)) = transition(grid, curr, curr_dir) {
+    while let Some((row, col, direction)) = transition(walls, curr, curr_dir) {
         curr = (row, col);
         curr_dir = direction;
-        visited.insert(curr);
+        visited.set(row, col);
     }
     visited
 }
 
-fn find_obstacles(
-    grid: &mut [Vec<char>],
-    start: (usize, usize),
-    visited: &HashSet<(usize, usize)>,
-) -> HashSet<(usize, usize)> {
-    let mut obstacles = HashSet::new();
-    for &(row, col) in visited {
-        grid[row][col] = '#';
-        if try_traversal(grid, start) {
-            obstacles.insert((row, col));
+fn find_obstacles(walls: &mut BitGrid, start: (usize, usize), visited: &BitGrid) -> BitGrid {
+    let mut obstacles = BitGrid::new(walls.rows(), walls.cols());
+    for (row, col) in visited.iter_ones() {
+        walls.set(row, col);
+        if try_traversal(walls, start) {
+            obstacles.set(row, col);
         }
-        grid[row][col] = '.';
+        walls.clear(row, col);
     }
     obstacles
 }
 
-fn try_traversal(grid: &[Vec<char>], start: (usize, usize)) -> bool {
-    let mut visited = HashSet::new();
-    let (mut curr, mut curr_dir) = (start, Direction::North);
-    visited.insert((curr, Direction::North));
-
-    while let Some((row, col, direction)) = transition(grid, curr, curr_dir) {
-        curr = (row, col);
-        curr_dir = direction;
-        if visited.contains(&(curr, curr_dir)) {
-            return true;
-        }
-        visited.insert((curr, curr_dir));
-    }
-    false
+/// Whether the guard ends up walking the same loop forever instead of leaving the grid.
+fn try_traversal(walls: &BitGrid, start: (usize, usize)) -> bool {
+    find_cycle((start, CardinalDirection::North), |&(curr, direction)| {
+        transition(walls, curr, direction).map(|(row, col, dir)| ((row, col), dir))
+    })
+    .is_some()
 }
 
 fn transition(
-    grid: &[Vec<char>],
+    walls: &BitGrid,
     curr: (usize, usize),
-    direction: Direction,
-) -> Option<(usize, usize, Direction)> {
-    let (m, n) = (grid.len(), grid[0].len());
+    direction: CardinalDirection,
+) -> Option<(usize, usize, CardinalDirection)> {
+    let (m, n) = (walls.rows(), walls.cols());
     let (row, col) = curr;
     let (next_row, next_col) = match direction {
-        Direction::North => {
+        CardinalDirection::North => {
             if row == 0 {
                 return None;
             }
             (row - 1, col)
         }
-        Direction::South => {
+        CardinalDirection::South => {
             if row == m - 1 {
                 return None;
             }
             (row + 1, col)
         }
-        Direction::East => {
+        CardinalDirection::East => {
             if col == n - 1 {
                 return None;
             }
             (row, col + 1)
         }
-        Direction::West => {
+        CardinalDirection::West => {
             if col == 0 {
                 return None;
             }
@@ -140,8 +106,8 @@ fn transition(
         }
     };
 
-    if grid[next_row][next_col] == '#' {
-        Some((row, col, direction.rotate()))
+    if walls.test(next_row, next_col) {
+        Some((row, col, direction.rotate(Rotation::Clockwise)))
     } else {
         Some((next_row, next_col, direction))
     }
@@ -1,38 +1,42 @@
-use std::ops::{Add, Mul};
+use std::ops::RangeInclusive;
+
+use advent_common::arithmetic::Operator;
+use advent_common::input::signed_ints;
 
 use crate::{AdventError, AdventProblem};
 
 pub struct Day7;
 
 impl AdventProblem for Day7 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let total = lines
-            .iter()
-            .map(Calibration::from)
-            .filter(|c| c.can_finish())
-            .map(|c| c.total)
-            .sum::<u64>();
-
-        println!("total={}", total);
-
-        Ok(0)
+        total_calibration(&lines, &[Operator::Add, Operator::Multiply])
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let total = lines
-            .iter()
-            .map(Calibration::from)
-            .filter(|c| c.can_finish_with_concatenate())
-            .map(|c| c.total)
-            .sum::<u64>();
-
-        println!("total={}", total);
+        total_calibration(
+            &lines,
+            &[Operator::Add, Operator::Multiply, Operator::Concat],
+        )
+    }
+}
 
-        Ok(0)
+/// Sum of the targets of every equation that some choice of `operators` makes true.
+fn total_calibration(lines: &[String], operators: &[Operator]) -> Result<u64, AdventError> {
+    let mut total = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let calibration = Calibration::try_from(line.as_str()).map_err(|e| e.at_line(i))?;
+        if calibration.solvable(operators) {
+            total += calibration.total;
+        }
     }
+    Ok(total)
 }
 
+/// Inverse ranges wider than this are checked by searching the prefix forwards instead of
+/// one candidate at a time.
+const MAX_CANDIDATES: u64 = 16;
+
 #[derive(Debug, PartialEq, Eq)]
 struct Calibration {
     total: u64,
@@ -40,59 +44,74 @@ struct Calibration {
 }
 
 impl Calibration {
-    fn can_finish(&self) -> bool {
-        self.try_evaluate(1, self.parts[0], &vec![u64::add, u64::mul])
+    fn solvable(&self, operators: &[Operator]) -> bool {
+        reaches(self.total, &self.parts, operators)
     }
+}
 
-    fn can_finish_with_concatenate(&self) -> bool {
-        self.try_evaluate(1, self.parts[0], &vec![u64::add, u64::mul, concatenate])
+/// Whether `parts`, combined left to right with `operators`, can evaluate to `target`.
+///
+/// Works backwards from the last part: each operator is undone to get the value the remaining
+/// prefix would need, and branches with no such value are dropped straight away. Undoing
+/// concatenation or multiplication usually fails, so very little of the tree gets visited.
+fn reaches(target: u64, parts: &[u64], operators: &[Operator]) -> bool {
+    let Some((&last, rest)) = parts.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return last == target;
     }
 
-    fn try_evaluate<F>(&self, idx: usize, running: u64, operators: &Vec<F>) -> bool
-    where
-        F: Fn(u64, u64) -> u64 + Clone,
-    {
-        if running > self.total {
-            return false;
-        }
-        if idx == self.parts.len() {
-            return running == self.total;
-        }
-
-        for op in operators {
-            if self.try_evaluate(idx + 1, op(running, self.parts[idx]), operators) {
-                return true;
-            }
+    operators.iter().any(|op| {
+        let needed = op.unapply(target, last);
+        if needed.is_empty() {
+            false
+        } else if needed.end() - needed.start() < MAX_CANDIDATES {
+            needed
+                .into_iter()
+                .any(|prev| reaches(prev, rest, operators))
+        } else {
+            let (&first, tail) = rest.split_first().expect("rest is not empty");
+            reaches_forward(first, tail, operators, &needed)
         }
-        false
-    }
+    })
 }
 
-fn concatenate(a: u64, b: u64) -> u64 {
-    let mut total = a.to_string();
-    total.push_str(&b.to_string());
-
-    total.parse::<u64>().expect("concatenate NaN")
+/// Whether `acc` combined with `parts` can land in `needed`, searching depth first and stopping
+/// at the first hit. Overflowing branches are dropped.
+fn reaches_forward(
+    acc: u64,
+    parts: &[u64],
+    operators: &[Operator],
+    needed: &RangeInclusive<u64>,
+) -> bool {
+    let Some((&part, rest)) = parts.split_first() else {
+        return needed.contains(&acc);
+    };
+    operators
+        .iter()
+        .filter_map(|op| op.apply(acc, part))
+        .any(|v| reaches_forward(v, rest, operators, needed))
 }
 
-impl<S> From<S> for Calibration
-where
-    S: AsRef<str>,
-{
-    fn from(s: S) -> Self {
-        let mut split = s.as_ref().split(":");
-        let total = split
-            .next()
-            .expect("missing total calibration value")
-            .parse::<u64>()
-            .expect("non-numeric total value");
-
-        let parts = split.next().expect("missing calibartion parts")[1..]
-            .split(" ")
-            .map(|s| s.parse::<u64>().expect("non-numeric calibration part"))
-            .collect();
-
-        Calibration { total, parts }
+impl TryFrom<&str> for Calibration {
+    type Error = AdventError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (total, parts) = s.split_once(':').ok_or_else(|| {
+            AdventError::InputParseError(format!("expected \"total: parts\", got {s:?}").into())
+        })?;
+        let parts = signed_ints::<u64>(parts)?;
+        if parts.is_empty() {
+            return Err(AdventError::InputParseError(
+                format!("no calibration parts in {s:?}").into(),
+            ));
+        }
+
+        Ok(Calibration {
+            total: total.trim().parse()?,
+            parts,
+        })
     }
 }
 
@@ -108,7 +127,7 @@ mod test {
                 total: 161011,
                 parts: vec![16, 10, 13],
             },
-            Calibration::from(line),
+            Calibration::try_from(line).unwrap(),
         );
     }
 
@@ -119,12 +138,7 @@ mod test {
             parts: vec![81, 40, 27],
         };
 
-        assert!(calibration.can_finish());
-    }
-
-    #[test]
-    fn test_concatenate() {
-        assert_eq!(123, concatenate(1, 23));
+        assert!(calibration.solvable(&[Operator::Add, Operator::Multiply]));
     }
 
     #[test]
@@ -134,6 +148,45 @@ mod test {
             parts: vec![6, 8, 6, 15],
         };
 
-        assert!(calibration.can_finish_with_concatenate());
+        assert!(!calibration.solvable(&[Operator::Add, Operator::Multiply]));
+        assert!(calibration.solvable(&[Operator::Add, Operator::Multiply, Operator::Concat]));
+    }
+
+    #[test]
+    fn sample() {
+        let lines = [
+            "190: 10 19",
+            "3267: 81 40 27",
+            "83: 17 5",
+            "156: 15 6",
+            "7290: 6 8 6 15",
+            "161011: 16 10 13",
+            "192: 17 8 14",
+            "21037: 9 7 18 13",
+            "292: 11 6 16 20",
+        ]
+        .map(String::from);
+
+        let basic = [Operator::Add, Operator::Multiply];
+        assert_eq!(3749, total_calibration(&lines, &basic).unwrap());
+        let with_concat = [Operator::Add, Operator::Multiply, Operator::Concat];
+        assert_eq!(11387, total_calibration(&lines, &with_concat).unwrap());
+    }
+
+    #[test]
+    fn wide_inverses() {
+        // Dividing and multiplying by zero leave many candidates for the prefix.
+        let ops = [Operator::Divide, Operator::Multiply, Operator::Add];
+        assert!(reaches(3, &[100, 30, 1], &ops));
+        assert!(reaches(5, &[7, 0, 5], &ops));
+        assert!(!reaches(4, &[7, 0, 5], &ops));
+
+        // Any prefix value works before "* 0"; the search stops at the first one instead of
+        // listing all 3^19 of them.
+        let mut parts = vec![1; 20];
+        parts.push(0);
+        let all = [Operator::Add, Operator::Multiply, Operator::Concat];
+        assert!(reaches(0, &parts, &all));
+        assert!(!reaches(0, &[1, 1, 1], &all));
     }
 }
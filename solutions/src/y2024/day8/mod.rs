@@ -228,7 +228,7 @@ fn next_antinode(
 
 /// helper function to take absolute difference without overflowing
 fn abs_diff(x: usize, y: usize) -> usize {
-    if x >= y { x - y } else { y - x }
+    x.abs_diff(y)
 }
 
 #[cfg(test)]
@@ -95,6 +95,7 @@ impl CompressedDiskMap {
         while i > 1 {
             let (data, n) = self.data[i];
             if data.is_some() {
+                let mut inserted = false;
                 for j in 0..i {
                     let (space, size) = self.data[j];
                     if space.is_none() && n <= size {
@@ -106,12 +107,15 @@ impl CompressedDiskMap {
                                 self.data[j + 1].1 += size - n;
                             } else {
                                 self.data.insert(j + 1, (None, size - n));
-                                i += 1;
+                                inserted = true;
                             }
                         }
                         break;
                     }
                 }
+                if inserted {
+                    i += 1;
+                }
             }
             i -= 1;
         }
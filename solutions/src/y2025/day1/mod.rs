@@ -1,9 +1,10 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::ring::Ring;
 use advent_common::rotation::Rotation;
 
 struct Turn {
     direction: Rotation,
-    magnitude: usize,
+    magnitude: u64,
 }
 
 impl TryFrom<&String> for Turn {
@@ -27,7 +28,7 @@ impl TryFrom<&String> for Turn {
         };
 
         let rem = &value[1..];
-        let mag = rem.parse::<usize>().map_err(|_e| {
+        let mag = rem.parse::<u64>().map_err(|_e| {
             AdventError::InputParseError(format!("val {rem} is not a number").into())
         })?;
 
@@ -40,75 +41,54 @@ impl TryFrom<&String> for Turn {
 
 pub struct Day1;
 
-const NUM_DIALS: usize = 100;
+type Dial = Ring<100>;
+
+const START: u64 = 50;
 
 impl AdventProblem for Day1 {
     type Answer = u32;
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut pos = 50;
+        let mut dial = Dial::new(START);
         let mut cnt = 0;
 
         for line in &lines {
             let turn = Turn::try_from(line)?;
-            let mag = turn.magnitude % 100;
-            match turn.direction {
-                Rotation::Clockwise => pos = (pos + mag) % NUM_DIALS,
-                Rotation::CounterClockwise => {
-                    if mag > pos {
-                        pos = NUM_DIALS + pos - mag;
-                    } else {
-                        pos -= mag;
-                    }
-                }
-            }
+            dial.turn(turn.direction, turn.magnitude);
 
-            if pos == 0 {
+            if dial.value() == 0 {
                 cnt += 1;
             }
         }
 
-        Ok(cnt as u32)
+        Ok(cnt)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut pos = 50;
+        let mut dial = Dial::new(START);
         let mut cnt = 0;
 
         for line in &lines {
             let turn = Turn::try_from(line)?;
-            let m = turn.magnitude;
-
-            cnt += m / NUM_DIALS;
-            let mag = m % NUM_DIALS;
-
-            match turn.direction {
-                Rotation::Clockwise => {
-                    // Remainder: extra hit iff we land on/pass 0 once more before the lap completes.
-                    if (pos + mag) >= NUM_DIALS {
-                        cnt += 1;
-                    }
-                    // (pos + q * NUM_DIALS + mag) mod NUM_DIALS == (pos + mag) mod NUM_DIALS
-                    pos = (pos + mag) % NUM_DIALS;
-                }
-                Rotation::CounterClockwise => {
-                    if pos == 0 {
-                        // From 0, hits occur at ticks NUM_DIALS, 2*NUM_DIALS, … within [1..mag].
-                        cnt += mag / NUM_DIALS;
-                    } else if mag >= pos {
-                        // Hits at pos, pos+NUM_DIALS, … up to mag.
-                        cnt += 1 + (mag - pos) / NUM_DIALS;
-                    }
-
-                    if mag > pos {
-                        pos = NUM_DIALS + pos - mag;
-                    } else {
-                        pos -= mag;
-                    }
-                }
-            }
+            cnt += dial.turn(turn.direction, turn.magnitude);
         }
 
         Ok(cnt as u32)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: [&str; 10] = [
+        "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+    ];
+
+    #[test]
+    fn sample() {
+        let lines = SAMPLE.map(String::from).to_vec();
+        assert_eq!(3, Day1.run_part_1(lines.clone()).unwrap());
+        assert_eq!(6, Day1.run_part_2(lines).unwrap());
+    }
+}
@@ -1,3 +1,4 @@
+use advent_common::gf2::{Gf2Matrix, Gf2Vector};
 use advent_common::number::NumSequence;
 
 use crate::{AdventError, AdventProblem};
@@ -16,10 +17,11 @@ impl AdventProblem for Day10 {
         let mut total = 0;
         for line in lines {
             let m = Machine::try_from(line.as_str())?;
-            total += m.min_presses();
+            total += m.min_presses().ok_or_else(|| {
+                AdventError::InputParseError(format!("no button presses reach {line}").into())
+            })?;
         }
 
-        // let res = presses.iter().fold(1, |p, s| p * s);
         Ok(total)
     }
 
@@ -49,16 +51,16 @@ impl TryFrom<&str> for Machine {
 
         let button_seq = parts[0].chars().collect::<Vec<_>>();
 
-        let requirements: Vec<bool> = (&button_seq[1..button_seq.len() - 1])
+        let requirements: Vec<bool> = button_seq[1..button_seq.len() - 1]
             .iter()
             .filter(|&&b| b == '.' || b == '#')
-            .map(|&b| if b == '#' { true } else { false })
+            .map(|&b| b == '#')
             .collect();
 
         let mut buttons = Vec::with_capacity(n - 2);
         let mut counters = Vec::with_capacity(10);
         for &rem in parts.iter().skip(1) {
-            if rem.chars().next() == Some('{') {
+            if rem.starts_with('{') {
                 counters = NumSequence::<usize>::try_from(rem)?.nums;
                 break;
             }
@@ -75,19 +77,6 @@ impl TryFrom<&str> for Machine {
     }
 }
 
-#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
-struct Position {
-    states: Vec<bool>,
-}
-
-impl Position {
-    fn new(n: usize) -> Self {
-        Self {
-            states: vec![false; n],
-        }
-    }
-}
-
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 struct Counter {
     states: Vec<usize>,
@@ -100,37 +89,18 @@ impl Counter {
 }
 
 impl Machine {
-    fn min_presses(&self) -> u64 {
-        let n: usize = self.requirements.len();
-        let mut min_steps = HashMap::new();
-        let start = Position::new(n);
-        min_steps.insert(start.clone(), 0);
-
-        let mut min_heap = BinaryHeap::new();
-        min_heap.push(Reverse((0, start.clone())));
-
-        let target = Position {
-            states: self.requirements.clone(),
-        };
-
-        while !min_steps.contains_key(&target) {
-            let (dist, pos) = min_heap.pop().unwrap().0;
-
-            if pos == target {
-                return dist;
-            }
-
-            for seq in &self.buttons {
-                let mut new_pos = pos.clone();
-                for &lt in seq {
-                    new_pos.states[lt] = !new_pos.states[lt];
-                }
-
-                min_heap.push(Reverse((dist + 1, new_pos)));
-            }
-        }
+    /// Each button is pressed at most once since pressing twice cancels out, so the presses are a
+    /// solution of `Ax = requirements` over GF(2) where column `i` of `A` is button `i`. The
+    /// fewest presses is the minimum-weight vector in that affine solution space.
+    fn min_presses(&self) -> Option<u64> {
+        let lights = Gf2Matrix::from_columns(
+            self.requirements.len(),
+            self.buttons.iter().map(|b| b.iter().copied()),
+        );
+        let target = Gf2Vector::from_bools(&self.requirements);
 
-        u64::MAX
+        let solution = lights.solve(&target)?;
+        Some(solution.min_weight().weight() as u64)
     }
 
     fn min_presses_p2(&self) -> u64 {
@@ -153,7 +123,7 @@ impl Machine {
 
         let mut objective = Expression::from(0.0);
         for &v in &x {
-            objective = objective + v;
+            objective += v;
         }
 
         let mut model = vars.minimise(objective).using(default_solver);
@@ -161,7 +131,7 @@ impl Machine {
             let mut lhs = Expression::from(0.0);
             for (i, btn) in self.buttons.iter().enumerate() {
                 if btn.contains(&j) {
-                    lhs = lhs + x[i];
+                    lhs += x[i];
                 }
             }
             model = model.with(lhs.eq(self.counters[j] as f64));
@@ -259,7 +229,22 @@ mod test {
             counters: vec![],
         };
 
-        assert_eq!(2, machine.min_presses());
+        assert_eq!(Some(2), machine.min_presses());
+
+        let m2 = Machine::try_from("[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}")
+            .unwrap();
+        assert_eq!(Some(3), m2.min_presses());
+
+        let m3 =
+            Machine::try_from("[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}")
+                .unwrap();
+        assert_eq!(Some(2), m3.min_presses());
+    }
+
+    #[test]
+    fn unreachable_lights() {
+        let m = Machine::try_from("[#.] (0,1) {1,1}").unwrap();
+        assert_eq!(None, m.min_presses());
     }
 
     #[test]
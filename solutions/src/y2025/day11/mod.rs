@@ -1,92 +1,60 @@
 use crate::{AdventError, AdventProblem};
-use std::collections::{HashMap, HashSet};
+use advent_common::graph::Graph;
 
 pub struct Day11;
 
 impl AdventProblem for Day11 {
-    type Answer = usize;
+    type Answer = u128;
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let server_rack = ServerRack::try_from(lines)?;
 
-        // let res = presses.iter().fold(1, |p, s| p * s);
-        Ok(server_rack.count_paths())
+        server_rack.count_paths(&YOU_TO_OUT)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let server_rack = ServerRack::try_from(lines)?;
 
-        // let res = presses.iter().fold(1, |p, s| p * s);
-        Ok(server_rack.count_dac_fft_paths())
-        // Ok(0)
+        server_rack.count_paths(&SVR_VIA_DAC_FFT)
     }
 }
 
-const START: &'static str = "you";
-const SVR: &'static str = "svr";
-const TERMINAL: &'static str = "out";
+/// Which device paths to count: every `start -> end` path that passes through all of `via`, in
+/// any order.
+struct PathQuery<'a> {
+    start: &'a str,
+    end: &'a str,
+    via: &'a [&'a str],
+}
+
+const YOU_TO_OUT: PathQuery = PathQuery {
+    start: "you",
+    end: "out",
+    via: &[],
+};
 
-const DAC: &'static str = "dac";
-const FFT: &'static str = "fft";
+const SVR_VIA_DAC_FFT: PathQuery = PathQuery {
+    start: "svr",
+    end: "out",
+    via: &["dac", "fft"],
+};
 
 struct ServerRack {
-    adj_list: HashMap<String, Vec<String>>,
+    graph: Graph<String>,
 }
 
 impl ServerRack {
-    fn count_paths(&self) -> usize {
-        let mut memo = HashMap::new();
-        self.path_count_exclusive(START, TERMINAL, &mut memo)
-    }
-
-    fn count_dac_fft_paths(&self) -> usize {
-        let mut memo = HashMap::new();
-
-        let dac_fft_paths = self.path_count_exclusive(DAC, FFT, &mut memo);
-        let fft_dac_paths = self.path_count_exclusive(FFT, DAC, &mut memo);
-        let st_fft_paths = self.path_count_exclusive(SVR, FFT, &mut memo);
-        let st_dac_paths = self.path_count_exclusive(SVR, DAC, &mut memo);
-        let dac_end_paths = self.path_count_exclusive(DAC, TERMINAL, &mut memo);
-        let fft_end_paths = self.path_count_exclusive(FFT, TERMINAL, &mut memo);
-
-        let branch_a = st_fft_paths
-            .saturating_mul(fft_dac_paths)
-            .saturating_mul(dac_end_paths);
-        let branch_b = st_dac_paths
-            .saturating_mul(dac_fft_paths)
-            .saturating_mul(fft_end_paths);
-        branch_a.saturating_add(branch_b)
-    }
-
-    /// Count of directed paths from `start` to `terminal`, using the **same traversal rules** as
-    /// before: stepping only along outgoing edges, with the `"out"` sentinel rule preserved.
-    /// `(start, terminal)` results are memoized — count is summed over outgoing neighbors without
-    /// materializing path strings.
-    fn path_count_exclusive(
-        &self,
-        start: &str,
-        terminal: &str,
-        memo: &mut HashMap<(String, String), usize>,
-    ) -> usize {
-        let key = (start.to_string(), terminal.to_string());
-        if let Some(&cached) = memo.get(&key) {
-            return cached;
-        }
-
-        let count = if start == TERMINAL && terminal != TERMINAL {
-            0
-        } else if start == terminal {
-            1
-        } else {
-            let mut total = 0usize;
-            for nei in self.adj_list.get(start).into_iter().flatten() {
-                total = total.saturating_add(self.path_count_exclusive(nei, terminal, memo));
-            }
-            total
-        };
-
-        memo.insert(key, count);
-        count
+    /// Exact count of directed paths matching `query`, stepping only along outgoing edges.
+    fn count_paths(&self, query: &PathQuery) -> Result<u128, AdventError> {
+        let from = self.graph.require(query.start)?;
+        let to = self.graph.require(query.end)?;
+        let via = query
+            .via
+            .iter()
+            .map(|&w| self.graph.require(w))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.graph.count_paths_through(from, to, &via)?)
     }
 }
 
@@ -94,23 +62,20 @@ impl<S: AsRef<str>> TryFrom<Vec<S>> for ServerRack {
     type Error = AdventError;
 
     fn try_from(lines: Vec<S>) -> Result<Self, Self::Error> {
-        // let mut nodes = HashSet::new();
-        let mut adj_list: HashMap<String, Vec<String>> = HashMap::new();
+        let mut graph = Graph::directed();
 
         for line in lines {
             let line_str = line.as_ref();
-            let n = &line_str[..3];
+            let (n, neighbors) = line_str.split_once(": ").ok_or_else(|| {
+                AdventError::InputParseError(format!("invalid device line {line_str}").into())
+            })?;
 
-            // nodes.insert(n.into());
-
-            let neighbors = line_str[5..].split(" ").collect::<Vec<_>>();
-            for nei in neighbors {
-                // nodes.insert(nei.into());
-                adj_list.entry(n.into()).or_default().push(nei.into());
+            for nei in neighbors.split(" ") {
+                graph.add_edge_ref(n, nei);
             }
         }
 
-        Ok(Self { adj_list })
+        Ok(Self { graph })
     }
 }
 
@@ -134,7 +99,7 @@ mod test {
         ];
 
         let rack = ServerRack::try_from(lines).unwrap();
-        assert_eq!(5, rack.count_paths());
+        assert_eq!(5, rack.count_paths(&YOU_TO_OUT).unwrap());
     }
 
     #[test]
@@ -156,6 +121,13 @@ mod test {
         ];
 
         let rack = ServerRack::try_from(lines).unwrap();
-        assert_eq!(2, rack.count_dac_fft_paths());
+        assert_eq!(2, rack.count_paths(&SVR_VIA_DAC_FFT).unwrap());
+
+        let via_hub = PathQuery {
+            start: "svr",
+            end: "out",
+            via: &["hub"],
+        };
+        assert_eq!(4, rack.count_paths(&via_hub).unwrap());
     }
 }
@@ -1,6 +1,10 @@
 //! Day 12 input: numbered tile bitmaps (`id:` + `#`/`.` grid) plus `WxH:` scalar rows.
 
 use crate::{AdventError, AdventProblem};
+use advent_common::bitgrid::BitGrid;
+use advent_common::exact_cover::ExactCover;
+use advent_common::input::{sections, signed_ints, signed_ints_n, Section};
+use advent_common::polyomino::{Cell, Polyomino};
 use std::borrow::Cow;
 
 pub struct Day12;
@@ -11,23 +15,16 @@ impl AdventProblem for Day12 {
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let puzzle = Puzzle::try_from_lines(&lines)?;
 
-        let mut cnt = 0;
-        for row in &puzzle.sized_rows {
-            let cell_req = row
-                .values
-                .iter()
-                .enumerate()
-                .map(|(i, &freq)| puzzle.tile_blocks[i].size * freq)
-                .sum::<usize>();
-
-            if cell_req <= row.height * row.width {
-                cnt += 1;
-            }
-        }
+        let cnt = puzzle
+            .sized_rows
+            .iter()
+            .filter(|row| row.fits(&puzzle.tile_blocks))
+            .count();
 
         Ok(cnt)
     }
 
+    /// The last day of the calendar only has one puzzle.
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let _data = Puzzle::try_from_lines(&lines)?;
         Ok(0)
@@ -49,7 +46,7 @@ pub struct TileBlock {
     pub id: u32,
     /// Number of `#` cells in `bitmap`.
     pub size: usize,
-    pub bitmap: Vec<Vec<bool>>,
+    pub bitmap: BitGrid,
 }
 
 /// One `width x height :` line with trailing integers.
@@ -60,63 +57,128 @@ pub struct SizedRow {
     pub values: Vec<usize>,
 }
 
-impl Puzzle {
-    /// Parse full text (e.g. puzzle file contents).
-    pub fn parse(input: &str) -> Result<Self, AdventError> {
-        let lines: Vec<&str> = input.lines().collect();
-        let mut i = 0usize;
-        let mut tile_blocks = Vec::new();
-        let mut sized_rows = Vec::new();
+impl TileBlock {
+    pub fn shape(&self) -> Polyomino {
+        Polyomino::from_grid(&self.bitmap)
+    }
 
-        while i < lines.len() {
-            let line = lines[i].trim();
-            if line.is_empty() {
-                i += 1;
-                continue;
-            }
+    /// Same for any two tiles that are rotations or reflections of each other.
+    pub fn canonical(&self) -> Polyomino {
+        self.shape().canonical()
+    }
+}
+
+impl SizedRow {
+    /// Whether `values[i]` copies of each `tiles[i]` can be laid in the region without overlap.
+    ///
+    /// Two cheap bounds settle most regions: the tiles cannot need more cells than the region
+    /// has, and tiles that each get their own square box of the largest tile's size always fit.
+    /// Anything in between is decided exactly by searching for a packing.
+    pub fn fits(&self, tiles: &[TileBlock]) -> bool {
+        let requested = || {
+            self.values
+                .iter()
+                .enumerate()
+                .filter(|&(_, &count)| count > 0)
+                .map(|(i, &count)| (&tiles[i], count))
+        };
+
+        let cells = requested().map(|(t, n)| t.size * n).sum::<usize>();
+        if cells > self.width * self.height {
+            return false;
+        }
 
-            if let Some(sr) = parse_sized_row_line(line)? {
-                sized_rows.push(sr);
-                i += 1;
-                continue;
+        let side = requested()
+            .map(|(t, _)| t.bitmap.rows().max(t.bitmap.cols()))
+            .max()
+            .unwrap_or(1);
+        let count = requested().map(|(_, n)| n).sum::<usize>();
+        if count <= (self.width / side) * (self.height / side) {
+            return true;
+        }
+
+        self.pack(requested())
+    }
+
+    /// Exact cover in which every cell is covered either by a tile copy or by one of the
+    /// single-cell "holes" left over, with one primary column per copy, per hole and per cell.
+    /// Making cells primary lets the search branch on the hardest cell to fill, and copies of
+    /// the same shape, like the holes, are interchangeable, even when they come from different
+    /// tiles that are rotations or reflections of each other.
+    fn pack<'a>(&self, requested: impl Iterator<Item = (&'a TileBlock, usize)>) -> bool {
+        let mut shapes: Vec<(Polyomino, usize)> = Vec::new();
+        for (tile, count) in requested {
+            let shape = tile.canonical();
+            match shapes.iter_mut().find(|(s, _)| *s == shape) {
+                Some((_, n)) => *n += count,
+                None => shapes.push((shape, count)),
             }
+        }
 
-            if let Some(id) = parse_tile_id_header(line) {
-                i += 1;
-                while i < lines.len() && lines[i].trim().is_empty() {
-                    i += 1;
+        let area = self.width * self.height;
+        let copies = shapes.iter().map(|(_, n)| n).sum::<usize>();
+        let holes = area - shapes.iter().map(|(s, n)| s.len() * n).sum::<usize>();
+        let cell = |(r, c): Cell| copies + holes + r * self.width + c;
+        let mut dlx = ExactCover::new(copies + holes + area, 0);
+
+        let mut copy = 0;
+        for (shape, count) in shapes {
+            let placements = shape.placements(self.height, self.width);
+            dlx.interchangeable(copy..copy + count);
+            for _ in 0..count {
+                for cells in &placements {
+                    let mut columns = vec![copy];
+                    columns.extend(cells.iter().map(|&c| cell(c)));
+                    dlx.add_row(&columns);
                 }
-                let mut bitmap = Vec::new();
-                while i < lines.len() {
-                    let row = lines[i].trim();
-                    if row.is_empty() {
-                        break;
-                    }
-                    if is_section_header(row) {
-                        break;
-                    }
-                    bitmap.push(parse_bitmap_row(row)?);
-                    i += 1;
-                }
-                if bitmap.is_empty() {
-                    return Err(invalid("tile block has no grid rows"));
-                }
-                let w = bitmap[0].len();
-                if bitmap.iter().any(|r| r.len() != w) {
-                    return Err(invalid("tile grid rows have different widths"));
+                copy += 1;
+            }
+        }
+
+        dlx.interchangeable(copies..copies + holes);
+        for hole in copies..copies + holes {
+            for r in 0..self.height {
+                for c in 0..self.width {
+                    dlx.add_row(&[hole, cell((r, c))]);
                 }
+            }
+        }
 
-                let hash_count = bitmap.iter().flatten().filter(|&&cell| cell).count();
+        dlx.first_solution().is_some()
+    }
+}
 
-                tile_blocks.push(TileBlock {
-                    id,
-                    size: hash_count,
-                    bitmap,
-                });
-                continue;
+impl Puzzle {
+    /// Each blank-line separated section is either a tile (`id:` followed by its grid) or a run
+    /// of `WxH:` rows.
+    pub fn try_from_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self, AdventError> {
+        let mut tile_blocks = Vec::new();
+        let mut sized_rows = Vec::new();
+        let mut row_lines = Vec::new();
+
+        for section in sections(lines) {
+            let (i, first) = section.iter().next().expect("sections are non-empty");
+            if let Some(id) = parse_tile_id_header(first) {
+                let tile = parse_tile_block(id, &section).map_err(|e| e.at_line(i))?;
+                tile_blocks.push(tile);
+            } else {
+                sized_rows.extend(section.parse_lines(parse_sized_row_line)?);
+                row_lines.extend(section.iter().map(|(i, _)| i));
             }
+        }
 
-            return Err(invalid(format!("unrecognized line: {line:?}")));
+        // Only checked once every tile is known, as the tiles could follow the regions.
+        if let Some((row, &i)) = sized_rows
+            .iter()
+            .zip(&row_lines)
+            .find(|(row, _)| row.values.len() > tile_blocks.len())
+        {
+            return Err(invalid(format!(
+                "{} tile counts but only {} tiles",
+                row.values.len(),
+                tile_blocks.len()
+            ))
+            .at_line(i));
         }
 
         Ok(Puzzle {
@@ -124,56 +186,57 @@ impl Puzzle {
             sized_rows,
         })
     }
-
-    pub fn try_from_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self, AdventError> {
-        Self::parse(
-            &lines
-                .iter()
-                .map(|s| s.as_ref())
-                .collect::<Vec<_>>()
-                .join("\n"),
-        )
-    }
 }
 
 fn invalid(msg: impl Into<Cow<'static, str>>) -> AdventError {
     AdventError::InputParseError(msg.into())
 }
 
-/// `WxH: v0 v1 ...`
-fn parse_sized_row_line(line: &str) -> Result<Option<SizedRow>, AdventError> {
-    let Some(colon) = line.find(':') else {
-        return Ok(None);
-    };
-    let (lhs, rhs) = line.split_at(colon);
-    let rhs = rhs[1..].trim();
-    let lhs = lhs.trim();
-    let Some((w, h)) = lhs.split_once('x').or_else(|| lhs.split_once('X')) else {
-        return Ok(None);
-    };
-    if w.is_empty()
-        || h.is_empty()
-        || !w.chars().all(|c| c.is_ascii_digit())
-        || !h.chars().all(|c| c.is_ascii_digit())
+/// `id:` header followed by `#`/`.` rows of equal width.
+fn parse_tile_block<S: AsRef<str>>(
+    id: u32,
+    section: &Section<'_, S>,
+) -> Result<TileBlock, AdventError> {
+    let rows = section.lines[1..]
+        .iter()
+        .map(|row| row.as_ref().trim())
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Err(invalid("tile block has no grid rows"));
+    }
+    if let Some(c) = rows
+        .iter()
+        .flat_map(|r| r.chars())
+        .find(|c| !"#.".contains(*c))
     {
-        return Ok(None);
+        return Err(invalid(format!("invalid tile character: {c:?}")));
     }
-    let width: usize = w.parse().map_err(|_| invalid("bad width in WxH line"))?;
-    let height: usize = h.parse().map_err(|_| invalid("bad height in WxH line"))?;
-
-    let values: Vec<usize> = if rhs.is_empty() {
-        Vec::new()
-    } else {
-        rhs.split_whitespace()
-            .map(|t| t.parse().map_err(|_| invalid("bad integer in WxH line")))
-            .collect::<Result<Vec<_>, _>>()?
+
+    let bitmap = BitGrid::from_lines(&rows, |c| c == '#')
+        .map_err(|_| invalid("tile grid rows have different widths"))?;
+
+    Ok(TileBlock {
+        id,
+        size: bitmap.count_ones(),
+        bitmap,
+    })
+}
+
+/// `WxH: v0 v1 ...`
+fn parse_sized_row_line(line: &str) -> Result<SizedRow, AdventError> {
+    let Some((lhs, rhs)) = line.split_once(':') else {
+        return Err(invalid(format!("unrecognized line: {line:?}")));
     };
+    if !lhs.contains(['x', 'X']) {
+        return Err(invalid(format!("expected `WxH:`, found {lhs:?}")));
+    }
+    let [width, height] = signed_ints_n(lhs)?;
 
-    Ok(Some(SizedRow {
+    Ok(SizedRow {
         width,
         height,
-        values,
-    }))
+        values: signed_ints(rhs)?,
+    })
 }
 
 /// Plain `id:` with no `x` before the colon (distinct from `12x5:`).
@@ -186,20 +249,6 @@ fn parse_tile_id_header(line: &str) -> Option<u32> {
     id_part.parse::<u32>().ok()
 }
 
-fn is_section_header(trimmed: &str) -> bool {
-    parse_tile_id_header(trimmed).is_some() || matches!(parse_sized_row_line(trimmed), Ok(Some(_)))
-}
-
-fn parse_bitmap_row(line: &str) -> Result<Vec<bool>, AdventError> {
-    line.chars()
-        .map(|c| match c {
-            '#' => Ok(true),
-            '.' => Ok(false),
-            _ => Err(invalid(format!("invalid tile character: {c:?}"))),
-        })
-        .collect()
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -241,25 +290,27 @@ mod test {
 
     #[test]
     fn parse_sample() {
-        let p = Puzzle::parse(SAMPLE).expect("parse");
+        let lines = SAMPLE.lines().collect::<Vec<_>>();
+        let p = Puzzle::try_from_lines(&lines).expect("parse");
 
         assert_eq!(p.tile_blocks.len(), 6);
 
         assert_eq!(p.tile_blocks[0].id, 0);
-        assert_eq!(
-            p.tile_blocks[0].bitmap,
-            vec![
-                vec![true, true, true],
-                vec![true, true, false],
-                vec![true, true, false],
-            ]
-        );
+        let bitmap = |rows: &[&str]| BitGrid::from_lines(rows, |c| c == '#').unwrap();
+        assert_eq!(p.tile_blocks[0].bitmap, bitmap(&["###", "##.", "##."]));
 
         assert_eq!(p.tile_blocks[0].size, 7);
 
         assert_eq!(p.tile_blocks[1].id, 1);
         assert_eq!(p.tile_blocks[1].size, 7);
-        assert_eq!(p.tile_blocks[1].bitmap[2], vec![false, true, true]);
+        assert_eq!(
+            vec![(2, 1), (2, 2)],
+            p.tile_blocks[1]
+                .bitmap
+                .iter_ones()
+                .filter(|&(r, _)| r == 2)
+                .collect::<Vec<_>>()
+        );
 
         assert_eq!(p.tile_blocks[2].size, 7);
 
@@ -269,14 +320,14 @@ mod test {
 
         assert_eq!(p.tile_blocks[5].id, 5);
         assert_eq!(p.tile_blocks[5].size, 7);
+        assert_eq!(p.tile_blocks[5].bitmap, bitmap(&["###", ".#.", "###"]));
+
         assert_eq!(
-            p.tile_blocks[5].bitmap,
-            vec![
-                vec![true, true, true],
-                vec![false, true, false],
-                vec![true, true, true],
-            ]
+            p.tile_blocks[4].canonical(),
+            p.tile_blocks[4].shape().rotated().flipped().canonical()
         );
+        assert_eq!(4, p.tile_blocks[4].shape().orientations().len());
+        assert_eq!(2, p.tile_blocks[5].shape().orientations().len());
 
         assert_eq!(p.sized_rows.len(), 3);
         assert_eq!(
@@ -304,4 +355,24 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn sample_packing() {
+        let lines = SAMPLE.lines().collect::<Vec<_>>();
+        let p = Puzzle::try_from_lines(&lines).expect("parse");
+
+        let fits = p
+            .sized_rows
+            .iter()
+            .map(|row| row.fits(&p.tile_blocks))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![true, true, false], fits);
+    }
+
+    #[test]
+    fn more_counts_than_tiles() {
+        let lines = ["0:", "#", "", "1x1: 1", "2x2: 0 1"];
+        let err = Puzzle::try_from_lines(&lines).unwrap_err();
+        assert_eq!("line 5: 2 tile counts but only 1 tiles", err.to_string());
+    }
 }
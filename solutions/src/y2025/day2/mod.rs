@@ -1,129 +1,94 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::digits::Digits;
+use advent_common::range::ParseRangeInclusive;
+use std::ops::RangeInclusive;
 
 pub struct Day2;
 
 impl AdventProblem for Day2 {
-    type Answer = u32;
+    type Answer = u64;
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut total_cnt = 0;
-
-        let s = &lines[0];
-        println!("{s}");
-
-        for part in lines[0].split(",") {
-            let mut num_split = part.split("-").take(2);
-            let (s, e) = (
-                num_split
-                    .next()
-                    .ok_or_else(|| {
-                        AdventError::InputParseError("missing numeric range start/end".into())
-                    })?
-                    .parse::<u64>()?,
-                num_split
-                    .next()
-                    .ok_or_else(|| {
-                        AdventError::InputParseError("missing numeric range start/end".into())
-                    })?
-                    .parse::<u64>()?,
-            );
-
-            total_cnt += invalid_id_sum(s, e, is_repeat_seq);
-        }
-
-        println!("total sum = {total_cnt}");
-        Ok(0)
-
-        // Ok(total_cnt as u64)
+        total_invalid(&lines, Repeats::Twice)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut total_cnt = 0;
-
-        let s = &lines[0];
-        println!("{s}");
-
-        for part in lines[0].split(",") {
-            let mut num_split = part.split("-").take(2);
-            let (s, e) = (
-                num_split
-                    .next()
-                    .ok_or_else(|| {
-                        AdventError::InputParseError("missing numeric range start/end".into())
-                    })?
-                    .parse::<u64>()?,
-                num_split
-                    .next()
-                    .ok_or_else(|| {
-                        AdventError::InputParseError("missing numeric range start/end".into())
-                    })?
-                    .parse::<u64>()?,
-            );
-
-            total_cnt += invalid_id_sum(s, e, is_multi_repeat_seq);
-        }
-
-        println!("total sum = {total_cnt}");
-        Ok(0)
+        total_invalid(&lines, Repeats::AtLeastTwice)
     }
 }
 
-fn invalid_id_sum<F: Fn(u64) -> bool>(s: u64, e: u64, f: F) -> u64 {
-    let mut cnt = 0;
-    for n in s..=e {
-        if f(n) {
-            cnt += n;
-        }
-    }
-    cnt
+/// How many times the digit block of an invalid ID is repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repeats {
+    Twice,
+    AtLeastTwice,
 }
 
-fn is_repeat_seq(id: u64) -> bool {
-    let chars = id.to_string().chars().collect::<Vec<char>>();
-
-    let n = chars.len();
-    if !n.is_multiple_of(2) {
-        return false;
+fn total_invalid(lines: &[String], repeats: Repeats) -> Result<u64, AdventError> {
+    let line = lines
+        .first()
+        .ok_or_else(|| AdventError::InputParseError("missing ID ranges".into()))?;
+    let mut total = 0;
+    for part in line.trim().split(',') {
+        let range: RangeInclusive<u64> = RangeInclusive::parse(part)?;
+        total += invalid_id_sum(*range.start(), *range.end(), repeats);
     }
-
-    for i in 0..(n / 2) {
-        if chars[i] != chars[n / 2 + i] {
-            return false;
-        }
-    }
-
-    true
+    u64::try_from(total).map_err(|_| {
+        AdventError::InputParseError(format!("invalid ID sum {total} does not fit in u64").into())
+    })
 }
 
-fn is_multi_repeat_seq(id: u64) -> bool {
-    let chars = id.to_string().chars().collect::<Vec<char>>();
-
-    let n = chars.len();
-
-    fn is_d_repeat_seq(chars: &[char], d: usize) -> bool {
-        let num_chunks = chars.len() / d;
-
-        for i in 0..d {
-            for j in 1..=(num_chunks - 1) {
-                if chars[i] != chars[i + j * d] {
-                    return false;
+/// Sum of the invalid IDs in `s..=e`, built from their digit blocks instead of scanning the
+/// range.
+///
+/// An ID of `len` digits made of a `period`-digit block `b` repeated is `b * m`, where `m` is
+/// `1` followed by `period - 1` zeros, repeated. Those IDs are evenly spaced, so the ones in
+/// range are an arithmetic series. An ID like `111111` is periodic with periods 1, 2 and 3, so
+/// for [`Repeats::AtLeastTwice`] each is counted under its smallest period only.
+fn invalid_id_sum(s: u64, e: u64, repeats: Repeats) -> u128 {
+    let mut total = 0u128;
+    for len in s.num_digits()..=e.num_digits() {
+        match repeats {
+            Repeats::Twice => {
+                if len.is_multiple_of(2) {
+                    total += periodic_sum(s, e, len, len / 2);
+                }
+            }
+            Repeats::AtLeastTwice => {
+                let periods = (1..len)
+                    .filter(|p| len.is_multiple_of(*p))
+                    .collect::<Vec<_>>();
+                // exact[i]: sum of IDs whose smallest period is periods[i].
+                let mut exact = Vec::with_capacity(periods.len());
+                for (i, &p) in periods.iter().enumerate() {
+                    let shorter = (0..i)
+                        .filter(|&j| p.is_multiple_of(periods[j]))
+                        .map(|j| exact[j])
+                        .sum::<u128>();
+                    exact.push(periodic_sum(s, e, len, p) - shorter);
                 }
+                total += exact.iter().sum::<u128>();
             }
         }
-
-        true
     }
+    total
+}
 
-    for d in (1..=n / 2).rev() {
-        if !n.is_multiple_of(d) {
-            continue;
-        }
-
-        if is_d_repeat_seq(&chars, d) {
-            return true;
-        }
+/// Sum of the `len`-digit IDs in `s..=e` that repeat a `period`-digit block.
+fn periodic_sum(s: u64, e: u64, len: u32, period: u32) -> u128 {
+    let pow10 = |k: u32| u128::pow10(k).expect("IDs fit in u64");
+    let lo = (s as u128).max(pow10(len - 1));
+    let hi = (e as u128).min(pow10(len) - 1);
+    if lo > hi {
+        return 0;
     }
 
-    false
+    let multiplier = (0..len / period).map(|i| pow10(i * period)).sum::<u128>();
+    let first = lo.div_ceil(multiplier).max(pow10(period - 1));
+    let last = (hi / multiplier).min(pow10(period) - 1);
+    if first > last {
+        return 0;
+    }
+    multiplier * (first + last) * (last - first + 1) / 2
 }
 
 #[cfg(test)]
@@ -140,7 +105,7 @@ mod test {
         ];
 
         for (s, e, expect) in cases {
-            assert_eq!(expect, invalid_id_sum(s, e, is_repeat_seq));
+            assert_eq!(expect, invalid_id_sum(s, e, Repeats::Twice));
         }
     }
 
@@ -154,7 +119,36 @@ mod test {
         ];
 
         for (s, e, expect) in cases {
-            assert_eq!(expect, invalid_id_sum(s, e, is_multi_repeat_seq));
+            assert_eq!(expect, invalid_id_sum(s, e, Repeats::AtLeastTwice));
         }
     }
+
+    #[test]
+    fn matches_scanning() {
+        let is_repeat = |id: u64, times: u32| {
+            let n = id.num_digits();
+            n.is_multiple_of(times)
+                && u64::repeat_block(id.split_at(n - n / times).0, times) == Some(id)
+        };
+        let scan = |s: u64, e: u64, f: &dyn Fn(u64) -> bool| {
+            (s..=e).filter(|&id| f(id)).map(u128::from).sum::<u128>()
+        };
+        for (s, e) in [(1, 200_000), (999_990, 1_011_000), (5, 5), (10, 10)] {
+            let twice = scan(s, e, &|id| is_repeat(id, 2));
+            let any = scan(s, e, &|id| (2..=id.num_digits()).any(|t| is_repeat(id, t)));
+            assert_eq!(twice, invalid_id_sum(s, e, Repeats::Twice));
+            assert_eq!(any, invalid_id_sum(s, e, Repeats::AtLeastTwice));
+        }
+    }
+
+    #[test]
+    fn huge_range() {
+        // The 12-digit IDs made of a 6-digit block twice, without visiting the range.
+        let blocks = (100_000..1_000_000)
+            .map(|b| u128::from(u64::repeat_block(b, 2).unwrap()))
+            .sum::<u128>();
+        let twelve_digits = invalid_id_sum(100_000_000_000, 999_999_999_999, Repeats::Twice);
+        assert_eq!(blocks, twelve_digits);
+        assert!(invalid_id_sum(1, u64::MAX, Repeats::AtLeastTwice) > u128::from(u64::MAX));
+    }
 }
@@ -8,7 +8,7 @@ impl AdventProblem for Day3 {
         let res = lines
             .iter()
             .map(|s| find_max_joltage(s.as_str(), 2))
-            .fold(0, |t, s| t + s);
+            .sum::<u64>();
 
         Ok(res)
     }
@@ -17,7 +17,7 @@ impl AdventProblem for Day3 {
         let res = lines
             .iter()
             .map(|s| find_max_joltage(s.as_str(), 12))
-            .fold(0, |t, s| t + s);
+            .sum::<u64>();
 
         Ok(res)
     }
@@ -37,8 +37,8 @@ fn find_max_joltage(s: &str, pick: usize) -> u64 {
 
         let end = n - (pick - p);
 
-        for i in f..=end {
-            let c = chars[i];
+        let start = f;
+        for (i, c) in chars.iter().enumerate().take(end + 1).skip(start) {
             let d = c.to_digit(10).expect("not a digit") as u64;
             if d > nxt {
                 nxt = d;
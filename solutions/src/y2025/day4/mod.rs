@@ -1,4 +1,4 @@
-use advent_common::floodfill::{try_transition, EIGHT_DIRECTIONS};
+use advent_common::bitgrid::BitGrid;
 
 use crate::{AdventError, AdventProblem};
 
@@ -8,10 +8,7 @@ impl AdventProblem for Day4 {
     type Answer = usize;
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
+        let grid = BitGrid::from_lines(&lines, is_paper_roll)?;
 
         let cnt = count_neighbor_squares(&grid);
 
@@ -19,10 +16,7 @@ impl AdventProblem for Day4 {
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let mut grid = lines
-            .iter()
-            .map(|s| s.chars().collect())
-            .collect::<Vec<Vec<_>>>();
+        let mut grid = BitGrid::from_lines(&lines, is_paper_roll)?;
 
         let max_cnt = simulate_removal(&mut grid);
         Ok(max_cnt)
@@ -33,77 +27,28 @@ fn is_paper_roll(c: char) -> bool {
     c == '@'
 }
 
-fn count_neighbor_squares(grid: &[Vec<char>]) -> usize {
-    let boundary = (grid.len(), grid[0].len());
-    let mut cnt = 0;
-
-    for (r, row) in grid.iter().enumerate() {
-        for (c, val) in row.iter().enumerate() {
-            if !is_paper_roll(*val) {
-                continue;
-            }
-
-            let mut nei_cnt = 0;
-            for dir in EIGHT_DIRECTIONS {
-                if let Some((nr, nc)) = try_transition((r, c), boundary, dir) {
-                    let nei_val = grid[nr][nc];
-                    if is_paper_roll(nei_val) {
-                        nei_cnt += 1;
-                    }
-                }
-            }
-
-            if nei_cnt < 4 {
-                cnt += 1;
-            }
-        }
-    }
+/// Paper rolls with fewer than four rolls among their eight neighbours.
+fn accessible(grid: &BitGrid) -> BitGrid {
+    let mut accessible = grid.neighbour_counts(true).less_than(4);
+    accessible.and_assign(grid);
+    accessible
+}
 
-    cnt
+fn count_neighbor_squares(grid: &BitGrid) -> usize {
+    accessible(grid).count_ones()
 }
 
-pub fn simulate_removal(grid: &mut [Vec<char>]) -> usize {
-    let mut has_removal = true;
+pub fn simulate_removal(grid: &mut BitGrid) -> usize {
     let mut total_cnt = 0;
-    let boundary = (grid.len(), grid[0].len());
-
-    while has_removal {
-        has_removal = false;
-        let mut cnt = 0;
-        let mut removed = vec![];
-
-        for (r, row) in grid.iter().enumerate() {
-            for (c, val) in row.iter().enumerate() {
-                if !is_paper_roll(*val) {
-                    continue;
-                }
-
-                let mut nei_cnt = 0;
-
-                for dir in EIGHT_DIRECTIONS {
-                    if let Some((nr, nc)) = try_transition((r, c), boundary, dir) {
-                        let nei_val = grid[nr][nc];
-                        if is_paper_roll(nei_val) {
-                            nei_cnt += 1;
-                        }
-                    }
-                }
-
-                if nei_cnt < 4 {
-                    has_removal = true;
-                    cnt += 1;
-                    removed.push((r, c));
-                }
-            }
-        }
 
-        for (r, c) in removed {
-            grid[r][c] = 'x';
+    loop {
+        let removed = accessible(grid);
+        if removed.is_empty() {
+            break;
         }
 
-        if has_removal {
-            total_cnt += cnt;
-        }
+        total_cnt += removed.count_ones();
+        grid.and_not_assign(&removed);
     }
 
     total_cnt
@@ -111,8 +56,6 @@ pub fn simulate_removal(grid: &mut [Vec<char>]) -> usize {
 
 #[cfg(test)]
 mod test {
-    use advent_common::floodfill::construct_grid;
-
     use super::*;
 
     #[test]
@@ -130,7 +73,7 @@ mod test {
             "@.@.@@@.@.",
         ];
 
-        let grid = construct_grid(&input);
+        let grid = BitGrid::from_lines(&input, is_paper_roll).unwrap();
         assert_eq!(13, count_neighbor_squares(&grid));
     }
 
@@ -149,12 +92,7 @@ mod test {
             "@.@.@@@.@.",
         ];
 
-        let mut grid = construct_grid(&input);
-        // let cnt = simulate_removal(&mut grid);
-
-        // for (r, row) in grid.iter().enumerate() {
-        // println!("row={row:?}")
-        // }
+        let mut grid = BitGrid::from_lines(&input, is_paper_roll).unwrap();
         assert_eq!(43, simulate_removal(&mut grid));
     }
 }
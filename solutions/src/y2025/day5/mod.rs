@@ -1,7 +1,9 @@
 use crate::{AdventError, AdventProblem};
+use advent_common::compress::CompressedAxis;
+use advent_common::input::split_sections;
 use advent_common::range::ParseRangeInclusive;
 // use core::range::Range;
-use std::{cmp::Ordering, ops::RangeInclusive};
+use std::ops::{Range, RangeInclusive};
 
 pub struct Day5;
 
@@ -10,12 +12,12 @@ impl AdventProblem for Day5 {
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let ingredient_db = IngredientDB::try_from(lines)?;
-        Ok(ingredient_db.count_fresh())
+        ingredient_db.count_fresh()
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let ingredient_db = IngredientDB::try_from(lines)?;
-        Ok(ingredient_db.range_count())
+        ingredient_db.range_count()
     }
 }
 
@@ -24,45 +26,72 @@ struct IngredientDB {
     ingredient_ids: Vec<usize>,
 }
 
+/// An ID as a coordinate on the compressed ID line.
+fn coordinate(id: usize) -> Result<i64, AdventError> {
+    i64::try_from(id).map_err(|_| {
+        AdventError::InputParseError(format!("ingredient ID {id} is too large").into())
+    })
+}
+
+/// `range` as the half-open coordinate range `start..end + 1`.
+fn half_open(range: &RangeInclusive<usize>) -> Result<Range<i64>, AdventError> {
+    let end = coordinate(*range.end())?.checked_add(1).ok_or_else(|| {
+        AdventError::InputParseError(format!("range {range:?} is too large").into())
+    })?;
+    Ok(coordinate(*range.start())?..end)
+}
+
 impl IngredientDB {
-    fn range_count(&self) -> usize {
-        let mut last_rng = self.fresh_ranges[0].clone();
-        let mut total_cnt = last_rng.end() - last_rng.start() + 1;
-
-        for rng in self.fresh_ranges.iter().skip(1) {
-            // if rng.start() < last_rng.end() || rng.end() < last_rng.end() {
-            //     continue;
-            // }
-
-            if last_rng.end() < rng.start() {
-                last_rng = rng.clone();
-                total_cnt += rng.end() - rng.start() + 1;
-            } else if last_rng.end() == rng.start() {
-                total_cnt += rng.end() - rng.start();
-                last_rng = RangeInclusive::new(last_rng.start().clone(), rng.end().clone())
-            } else {
-                if rng.end() <= last_rng.end() {
-                    continue;
-                }
-
-                let end = std::cmp::max(last_rng.end(), rng.end());
-                total_cnt += end - last_rng.end();
-                last_rng = RangeInclusive::new(last_rng.start().clone(), end.clone());
-            }
+    /// Splits the ID line at every range boundary and marks the slots that some range covers.
+    fn coverage(&self) -> Result<(CompressedAxis, Vec<bool>), AdventError> {
+        let bounds = self
+            .fresh_ranges
+            .iter()
+            .map(half_open)
+            .collect::<Result<Vec<_>, _>>()?;
+        let axis = CompressedAxis::from_bounds(bounds.iter().flat_map(|r| [r.start, r.end]));
+
+        let mut depth = vec![0i32; axis.len() + 1];
+        for range in bounds {
+            let slots = axis
+                .slots(range)
+                .expect("range ends are breakpoints of the axis");
+            depth[slots.start] += 1;
+            depth[slots.end] -= 1;
         }
 
-        total_cnt
+        let mut open = 0;
+        let covered = depth[..axis.len()]
+            .iter()
+            .map(|d| {
+                open += d;
+                open > 0
+            })
+            .collect();
+        Ok((axis, covered))
     }
 
-    fn count_fresh(&self) -> usize {
-        self.ingredient_ids
-            .iter()
-            .filter(|&id| self.is_fresh(*id))
-            .count()
+    fn range_count(&self) -> Result<usize, AdventError> {
+        let (axis, covered) = self.coverage()?;
+        Ok((0..axis.len())
+            .filter(|&i| covered[i])
+            .map(|i| axis.width(i) as usize)
+            .sum())
     }
 
-    fn is_fresh(&self, id: usize) -> bool {
-        self.fresh_ranges.iter().any(|rng| rng.contains(&id))
+    /// IDs past the end of the coordinate line are beyond every range, so they are not fresh.
+    fn count_fresh(&self) -> Result<usize, AdventError> {
+        let (axis, covered) = self.coverage()?;
+        Ok(self
+            .ingredient_ids
+            .iter()
+            .filter(|&&id| {
+                coordinate(id)
+                    .ok()
+                    .and_then(|x| axis.locate(x))
+                    .is_some_and(|i| covered[i])
+            })
+            .count())
     }
 }
 
@@ -70,31 +99,9 @@ impl TryFrom<Vec<String>> for IngredientDB {
     type Error = AdventError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
-        let mut fresh_ranges: Vec<RangeInclusive<usize>> = vec![];
-        let mut ingredient_ids: Vec<usize> = vec![];
-
-        let mut i = 0;
-        while lines[i] != "" {
-            let rng = RangeInclusive::parse(&lines[i])?;
-            fresh_ranges.push(rng);
-            i += 1;
-        }
-
-        i += 1;
-
-        while i < lines.len() {
-            let line = &lines[i];
-            ingredient_ids.push(line.parse::<usize>()?);
-            i += 1;
-        }
-
-        fresh_ranges.sort_by(|r1, r2| {
-            let s_cmp = r1.start().cmp(r2.start());
-            if s_cmp != Ordering::Equal {
-                return s_cmp;
-            }
-            r1.end().cmp(r2.end())
-        });
+        let [ranges, ids] = split_sections(&lines)?;
+        let fresh_ranges: Vec<RangeInclusive<usize>> = ranges.parse_lines(RangeInclusive::parse)?;
+        let ingredient_ids = ids.parse_lines(|line| Ok(line.parse::<usize>()?))?;
 
         Ok(Self {
             fresh_ranges,
@@ -119,7 +126,7 @@ mod test {
             ingredient_ids: vec![1, 5, 8, 11, 17, 32],
         };
 
-        assert_eq!(3, db.count_fresh());
+        assert_eq!(3, db.count_fresh().unwrap());
     }
 
     #[test]
@@ -134,6 +141,22 @@ mod test {
             ingredient_ids: vec![1, 5, 8, 11, 17, 32],
         };
 
-        assert_eq!(14, db.range_count());
+        assert_eq!(14, db.range_count().unwrap());
+    }
+
+    #[test]
+    fn ids_beyond_i64() {
+        let huge = i64::MAX as usize + 1;
+        let db = IngredientDB {
+            fresh_ranges: vec![RangeInclusive::new(3, 5)],
+            ingredient_ids: vec![4, huge],
+        };
+        assert_eq!(1, db.count_fresh().unwrap());
+
+        let db = IngredientDB {
+            fresh_ranges: vec![RangeInclusive::new(3, i64::MAX as usize)],
+            ingredient_ids: vec![4],
+        };
+        assert!(db.range_count().is_err());
     }
 }
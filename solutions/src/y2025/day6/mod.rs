@@ -1,5 +1,6 @@
 use crate::{AdventError, AdventProblem};
 use advent_common::arithmetic::Operator;
+use advent_common::rotation::transpose;
 
 pub struct Day6;
 
@@ -8,12 +9,12 @@ impl AdventProblem for Day6 {
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let hwk = MathHomework::try_from(lines)?;
-        Ok(homework_total(&hwk.groups))
+        homework_total(&hwk.groups)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
         let hwk = CephalopodHomework::try_from(lines)?;
-        Ok(homework_total(&hwk.groups))
+        homework_total(&hwk.groups)
     }
 }
 
@@ -33,14 +34,6 @@ struct NumberGroup {
     nums: Vec<u32>,
 }
 
-#[derive(Debug)]
-struct NumberGrid {
-    operator: Operator,
-    /// Width in characters for this block on each data row.
-    width: usize,
-    nums: Vec<Vec<Option<u32>>>,
-}
-
 /// One segment from the last line: operator character followed by a run of spaces that
 /// delimits the next operator. The final column has no trailing spaces; its width is
 /// `spaces_after + 1` so the last digit column is included.
@@ -50,17 +43,19 @@ struct ColumnSpec {
     width: usize,
 }
 
-fn homework_total(groups: &[NumberGroup]) -> u64 {
+fn homework_total(groups: &[NumberGroup]) -> Result<u64, AdventError> {
     groups.iter().map(NumberGroup::reduce).sum()
 }
 
 impl NumberGroup {
-    fn reduce(&self) -> u64 {
-        match self.operator {
-            Operator::Add => self.nums.iter().fold(0u64, |s, &n| s + n as u64),
-            Operator::Multiply => self.nums.iter().fold(1u64, |p, &n| p * n as u64),
-            _ => unimplemented!("operator not implemented"),
-        }
+    fn reduce(&self) -> Result<u64, AdventError> {
+        self.operator
+            .fold(self.nums.iter().map(|&n| n as u64))
+            .ok_or_else(|| {
+                AdventError::InputParseError(
+                    format!("cannot apply {:?} to {:?}", self.operator, self.nums).into(),
+                )
+            })
     }
 }
 
@@ -110,63 +105,49 @@ impl<S: AsRef<str>> TryFrom<Vec<S>> for CephalopodHomework {
         }
 
         let specs = parse_cephalopod_operator_layout(lines[n - 1].as_ref())?;
-        let mut grids = specs
-            .into_iter()
-            .map(|spec| NumberGrid {
-                operator: spec.operator,
-                width: spec.width,
-                nums: vec![],
-            })
+        let rows = lines[..n - 1]
+            .iter()
+            .map(|line| line.as_ref().chars().collect::<Vec<_>>())
             .collect::<Vec<_>>();
 
-        for line in lines.iter().take(n - 1) {
-            let chars: Vec<char> = line.as_ref().chars().collect();
-            let mut start = 0usize;
-
-            for g in grids.iter_mut() {
-                let end = start + g.width;
-                let mut row = vec![None; g.width];
-
-                for idx in start..end {
-                    let c = chars[idx];
-                    if c == ' ' {
-                        continue;
-                    }
-
-                    let d = c.to_digit(10).ok_or_else(|| {
-                        AdventError::InputParseError(
-                            format!("expected digit or space, got {c:?}").into(),
-                        )
-                    })?;
-                    row[idx - start] = Some(d);
-                }
-
-                start = end + 1;
-                g.nums.push(row);
-            }
-        }
+        let mut groups = Vec::with_capacity(specs.len());
+        let mut start = 0usize;
+        for spec in specs {
+            let end = start + spec.width;
+            let block = rows
+                .iter()
+                .map(|row| {
+                    let mut cells = row.get(start..end.min(row.len())).unwrap_or(&[]).to_vec();
+                    cells.resize(spec.width, ' ');
+                    cells
+                })
+                .collect::<Vec<_>>();
+
+            // Each number is written top to bottom in one column, so it is a row of the
+            // transposed block.
+            let nums = transpose(&block)
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .filter(|&&c| c != ' ')
+                        .try_fold(0u32, |agg, &c| {
+                            let d = c.to_digit(10).ok_or_else(|| {
+                                AdventError::InputParseError(
+                                    format!("expected digit or space, got {c:?}").into(),
+                                )
+                            })?;
+                            Ok(agg * 10 + d)
+                        })
+                })
+                .collect::<Result<Vec<_>, AdventError>>()?;
 
-        let groups = grids
-            .iter()
-            .map(|g| {
-                let nums: Vec<u32> = (0..g.width)
-                    .map(|col| {
-                        let mut agg = 0u32;
-                        for row in 0..(n - 1) {
-                            if let Some(d) = g.nums[row][col] {
-                                agg = agg * 10 + d;
-                            }
-                        }
-                        agg
-                    })
-                    .collect();
-
-                NumberGroup {
-                    operator: g.operator,
-                    nums,
-                }
-            })
-            .collect();
+            groups.push(NumberGroup {
+                operator: spec.operator,
+                nums,
+            });
+            start = end + 1;
+        }
 
         Ok(Self { groups })
     }
@@ -223,7 +204,7 @@ mod test {
 
         let hwk = MathHomework::try_from(lines).expect("bad input");
 
-        assert_eq!(4277556, homework_total(&hwk.groups));
+        assert_eq!(4277556, homework_total(&hwk.groups).unwrap());
     }
 
     #[test]
@@ -237,7 +218,7 @@ mod test {
 
         let hwk = CephalopodHomework::try_from(lines).expect("bad input");
 
-        assert_eq!(3263827, homework_total(&hwk.groups));
+        assert_eq!(3263827, homework_total(&hwk.groups).unwrap());
     }
 
     #[test]
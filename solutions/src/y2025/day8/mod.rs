@@ -1,8 +1,8 @@
 use crate::{AdventError, AdventProblem};
-use advent_common::number::XYZCoord;
-use advent_common::ufind::UnionFind;
+use advent_common::kdtree::KdTree;
+use advent_common::point3::Point3;
+use advent_common::ufind::{minimum_spanning_forest, UnionFind};
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
 
 pub struct Day8;
 
@@ -10,83 +10,112 @@ impl AdventProblem for Day8 {
     type Answer = usize;
 
     fn run_part_1(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let junctions = lines
-            .iter()
-            .map(|s| XYZCoord::try_from(s.as_str()))
-            .collect::<Result<Vec<_>, AdventError>>()?;
+        let junctions = parse_junctions(&lines)?;
 
-        let (mut uf, _) = connect_junctions(&junctions, 1000);
+        let mut uf = connect_junctions(&junctions, 1000);
         let (f, s, t) = get_three_largest_groups(&mut uf);
         Ok(f * s * t)
     }
 
     fn run_part_2(&self, lines: Vec<String>) -> Result<Self::Answer, AdventError> {
-        let junctions = lines
-            .iter()
-            .map(|s| XYZCoord::try_from(s.as_str()))
-            .collect::<Result<Vec<_>, AdventError>>()?;
-
-        let num_junctions = junctions.len();
-        println!("junctions={num_junctions}");
-
-        let (mut min, mut max) = (1000, 10_000);
-        let mut last_conn = (0, 0);
-        let mut last_uf = None;
-
-        while min < max {
-            let mid = (min + max) / 2;
-            let (mut uf, lc) = connect_junctions(&junctions, mid);
-
-            if uf.disjoint_groups().len() == 1 {
-                max = mid;
-                last_uf = Some(uf);
-                last_conn = lc;
-            } else {
-                min = mid + 1;
-            }
-        }
-
-        let mut groups = last_uf.unwrap().disjoint_groups();
-        groups.sort_by_key(|&(size, _)| Reverse(size));
-
-        let (first, second) = last_conn;
-
-        let j1 = &junctions[first].clone();
-        let j2: &XYZCoord = &junctions[second].clone();
-
-        Ok((j1.x * j2.x) as usize)
+        let junctions = parse_junctions(&lines)?;
+
+        let (first, second) = last_connection(&junctions).ok_or_else(|| {
+            AdventError::InputParseError("need at least two junctions to connect".into())
+        })?;
+
+        let (a, b) = (junctions[first].x, junctions[second].x);
+        a.checked_mul(b)
+            .and_then(|product| usize::try_from(product).ok())
+            .ok_or_else(|| {
+                AdventError::InputParseError(
+                    format!("X coordinates {a} * {b} do not give a usize answer").into(),
+                )
+            })
     }
 }
 
-fn connect_junctions(junctions: &[XYZCoord], num_iter: usize) -> (UnionFind, (usize, usize)) {
-    let n = junctions.len();
-
-    let mut union_find = UnionFind::new(n);
-    let mut pq = BinaryHeap::new();
-    let mut processed_nodes = vec![false; n];
+fn parse_junctions(lines: &[String]) -> Result<Vec<Point3>, AdventError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, s)| Point3::try_from(s.as_str()).map_err(|e| e.at_line(i)))
+        .collect()
+}
 
-    for (i, j1) in junctions.iter().enumerate() {
-        for (j, j2) in junctions.iter().enumerate().skip(i + 1) {
-            let dist = j1.dist_sq(j2);
-            pq.push(Reverse((dist, i, j)));
+type Edge = (u64, usize, usize);
+
+/// Edges from every junction to its `k` nearest neighbours, sorted by distance and then by
+/// endpoints. Any pair that is missing is at least as far apart as the returned bound, so the
+/// list holds every pair closer than it.
+fn candidate_edges(tree: &KdTree, k: usize) -> (Vec<Edge>, u64) {
+    let mut edges = Vec::with_capacity(tree.len() * k);
+    let mut complete_below = u64::MAX;
+    for i in 0..tree.len() {
+        let nearest = tree.nearest(&tree.point(i), k + 1);
+        if nearest.len() == k + 1 {
+            complete_below = complete_below.min(nearest[k].0);
         }
+        edges.extend(
+            nearest
+                .into_iter()
+                .filter(|&(_, j)| j != i)
+                .map(|(d, j)| (d, i.min(j), i.max(j))),
+        );
     }
+    edges.sort_unstable();
+    edges.dedup();
+    (edges, complete_below)
+}
 
-    let mut last_conn = (0, 0);
-
-    for n_iter in 0..num_iter {
-        let (_, i, j) = pq.pop().unwrap().0;
+/// Runs `accept` on candidate edge lists with more and more neighbours per junction until it
+/// returns an answer.
+fn with_candidates<T>(
+    junctions: &[Point3],
+    mut accept: impl FnMut(&[Edge], u64) -> Option<T>,
+) -> T {
+    let tree = KdTree::new(junctions);
+    let mut k = 8;
+    loop {
+        let (edges, complete_below) = candidate_edges(&tree, k);
+        if let Some(answer) = accept(&edges, complete_below) {
+            return answer;
+        }
+        k *= 2;
+    }
+}
 
+/// Connects the `count` closest pairs of junctions, whether or not they are already in the
+/// same circuit.
+fn connect_junctions(junctions: &[Point3], count: usize) -> UnionFind {
+    let mut union_find = UnionFind::new(junctions.len());
+    let Some(last) = count.checked_sub(1) else {
+        return union_find;
+    };
+    let closest = with_candidates(junctions, |edges, complete_below| {
+        let enough = edges.get(last).is_some_and(|e| e.0 < complete_below);
+        (enough || complete_below == u64::MAX).then(|| edges[..count.min(edges.len())].to_vec())
+    });
+
+    for (_, i, j) in closest {
         union_find.connect(i, j);
-        processed_nodes[i] = true;
-        processed_nodes[j] = true;
-
-        if n_iter == num_iter - 1 {
-            last_conn = (i, j);
-        }
     }
+    union_find
+}
 
-    (union_find, last_conn)
+/// The pair whose connection joins every junction into one circuit: the last edge of the
+/// minimum spanning tree.
+fn last_connection(junctions: &[Point3]) -> Option<(usize, usize)> {
+    if junctions.len() < 2 {
+        return None;
+    }
+    let last = with_candidates(junctions, |edges, complete_below| {
+        let (tree, _) = minimum_spanning_forest(junctions.len(), edges.iter().copied());
+        let &(d, i, j) = tree.last()?;
+        let exact = d < complete_below || complete_below == u64::MAX;
+        (tree.len() + 1 == junctions.len() && exact).then_some((i, j))
+    });
+    Some(last)
 }
 
 fn get_three_largest_groups(u: &mut UnionFind) -> (usize, usize, usize) {
@@ -101,33 +130,45 @@ mod test {
     use super::*;
 
     #[test]
-    fn sample_part_1() {
+    fn sample() {
         let junctions = vec![
-            XYZCoord::new(162, 817, 812),
-            XYZCoord::new(57, 618, 57),
-            XYZCoord::new(906, 360, 560),
-            XYZCoord::new(592, 479, 940),
-            XYZCoord::new(352, 342, 300),
-            XYZCoord::new(466, 668, 158),
-            XYZCoord::new(542, 29, 236),
-            XYZCoord::new(431, 825, 988),
-            XYZCoord::new(739, 650, 466),
-            XYZCoord::new(52, 470, 668),
-            XYZCoord::new(216, 146, 977),
-            XYZCoord::new(819, 987, 18),
-            XYZCoord::new(117, 168, 530),
-            XYZCoord::new(805, 96, 715),
-            XYZCoord::new(346, 949, 466),
-            XYZCoord::new(970, 615, 88),
-            XYZCoord::new(941, 993, 340),
-            XYZCoord::new(862, 61, 35),
-            XYZCoord::new(984, 92, 344),
-            XYZCoord::new(425, 690, 689),
+            Point3::new(162, 817, 812),
+            Point3::new(57, 618, 57),
+            Point3::new(906, 360, 560),
+            Point3::new(592, 479, 940),
+            Point3::new(352, 342, 300),
+            Point3::new(466, 668, 158),
+            Point3::new(542, 29, 236),
+            Point3::new(431, 825, 988),
+            Point3::new(739, 650, 466),
+            Point3::new(52, 470, 668),
+            Point3::new(216, 146, 977),
+            Point3::new(819, 987, 18),
+            Point3::new(117, 168, 530),
+            Point3::new(805, 96, 715),
+            Point3::new(346, 949, 466),
+            Point3::new(970, 615, 88),
+            Point3::new(941, 993, 340),
+            Point3::new(862, 61, 35),
+            Point3::new(984, 92, 344),
+            Point3::new(425, 690, 689),
         ];
 
-        let (mut res, _) = connect_junctions(&junctions, 10);
+        let mut res = connect_junctions(&junctions, 10);
         let (f, s, t) = get_three_largest_groups(&mut res);
 
         assert_eq!(40, f * s * t);
+
+        let mut unconnected = connect_junctions(&junctions, 0);
+        assert_eq!(junctions.len(), unconnected.component_count());
+
+        let (first, second) = last_connection(&junctions).unwrap();
+        assert_eq!(25272, junctions[first].x * junctions[second].x);
+    }
+
+    #[test]
+    fn negative_answer() {
+        let lines = vec!["-2,0,0".to_string(), "3,0,0".to_string()];
+        assert!(Day8.run_part_2(lines).is_err());
     }
 }